@@ -0,0 +1,41 @@
+//! Time `parse_events` over captured events, using a window that covers
+//! all of them. Defaults to the test fixture; set `MYGHSTATUS_BENCH_EVENTS`
+//! to a file saved from the API (as for `--from-file`) to time your own.
+
+use chrono::prelude::*;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use mygithubstatus::{parse_events, read_events_file};
+
+fn bench_parse(c: &mut Criterion) {
+    let path = std::env::var("MYGHSTATUS_BENCH_EVENTS").unwrap_or_else(|_| {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/events.json").to_string()
+    });
+    let events: Vec<_> = read_events_file(&path)
+        .unwrap_or_else(|e| panic!("Reading {}: {}", path, e))
+        .into_iter()
+        .map(Box::new)
+        .collect();
+    let (first, last) = match (
+        events.iter().map(|e| e.created_at).min(),
+        events.iter().map(|e| e.created_at).max(),
+    ) {
+        (Some(first), Some(last)) => (first, last),
+        _ => panic!("No events in {}", path),
+    };
+    let start = (first - chrono::Duration::seconds(1)).with_timezone(&Local);
+    let end = (last + chrono::Duration::seconds(1)).with_timezone(&Local);
+
+    let mut g = c.benchmark_group("parse_events");
+    g.throughput(Throughput::Elements(events.len() as u64));
+    g.bench_function("events", |b| {
+        b.iter_batched(
+            || events.clone(),
+            |events| parse_events(events, &start, &end),
+            BatchSize::SmallInput,
+        )
+    });
+    g.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
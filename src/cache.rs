@@ -0,0 +1,148 @@
+//! On-disk cache of parsed and enriched activity, so re-running the same
+//! query (e.g. while trying out formats or delivery options) doesn't fetch
+//! everything again.
+
+use crate::RepoEventParseData;
+use anyhow::Result;
+use chrono::prelude::*;
+use serde_derive::*;
+use std::path::PathBuf;
+
+/// The activity in one window, as of `created`.
+#[derive(Deserialize)]
+pub(crate) struct CachedEvents {
+    pub(crate) created: DateTime<Utc>,
+    /// How many events were fetched before filtering
+    pub(crate) fetched: u32,
+    pub(crate) events: RepoEventParseData,
+}
+
+#[derive(Serialize)]
+struct CachedEventsRef<'a> {
+    created: DateTime<Utc>,
+    fetched: u32,
+    events: &'a RepoEventParseData,
+}
+
+pub(crate) struct ReportCache {
+    dir: PathBuf,
+    ttl: chrono::Duration,
+}
+
+/// Where the cache lives: `cache` under `$MYGHSTATUS_DATA_DIR` if that is
+/// set, otherwise the platform's cache directory (`$XDG_CACHE_HOME/mygithubstatus`
+/// or `~/.cache/mygithubstatus` on Linux, `~/Library/Caches` on macOS,
+/// `%LOCALAPPDATA%` on Windows).
+pub(crate) fn dir() -> Option<PathBuf> {
+    if let Some(d) = std::env::var_os("MYGHSTATUS_DATA_DIR") {
+        return Some(PathBuf::from(d).join("cache"));
+    }
+    directories::ProjectDirs::from("", "", "mygithubstatus").map(|d| d.cache_dir().to_path_buf())
+}
+
+impl ReportCache {
+    pub(crate) fn new(ttl_secs: u64) -> Option<Self> {
+        Some(Self {
+            dir: dir()?,
+            ttl: chrono::Duration::seconds(ttl_secs as i64),
+        })
+    }
+
+    pub(crate) fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Look up unexpired activity; any error just counts as a miss.
+    pub(crate) fn load(&self, key: &str) -> Option<CachedEvents> {
+        let f = std::fs::File::open(self.path(key)).ok()?;
+        let r: CachedEvents = match serde_json::from_reader(std::io::BufReader::new(f)) {
+            Ok(r) => r,
+            Err(e) => {
+                log::debug!("Ignoring unreadable cache entry {}: {}", key, e);
+                return None;
+            }
+        };
+        if Utc::now() - r.created > self.ttl {
+            return None;
+        }
+        Some(r)
+    }
+
+    /// Save activity under `key`, dropping any expired entries.
+    pub(crate) fn store(&self, key: &str, fetched: u32, events: &RepoEventParseData) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        self.prune(self.ttl)?;
+        let entry = CachedEventsRef {
+            created: Utc::now(),
+            fetched,
+            events,
+        };
+        let tmp = self.dir.join(format!("{}.json.tmp", key));
+        std::fs::write(&tmp, serde_json::to_vec(&entry)?)?;
+        std::fs::rename(&tmp, self.path(key))?;
+        Ok(())
+    }
+
+    /// Remove every entry, returning how many.
+    pub(crate) fn clear(&self) -> Result<usize> {
+        self.prune(chrono::Duration::zero())
+    }
+
+    /// Remove entries last written more than `age` ago, returning how many.
+    pub(crate) fn prune(&self, age: chrono::Duration) -> Result<usize> {
+        let age = age.to_std().unwrap_or_default();
+        let mut n = 0;
+        for e in entries(&self.dir)? {
+            if e.modified.elapsed().is_ok_and(|a| a >= age) {
+                std::fs::remove_file(&e.path)?;
+                n += 1;
+            }
+        }
+        Ok(n)
+    }
+}
+
+/// A file in the cache directory.
+pub(crate) struct Entry {
+    pub(crate) path: PathBuf,
+    pub(crate) modified: std::time::SystemTime,
+    pub(crate) size: u64,
+}
+
+/// The files in the cache directory `dir`, if it exists.
+pub(crate) fn entries(dir: &std::path::Path) -> Result<Vec<Entry>> {
+    let rd = match std::fs::read_dir(dir) {
+        Ok(rd) => rd,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut r = Vec::new();
+    for e in rd {
+        let e = e?;
+        let meta = e.metadata()?;
+        if meta.is_file() {
+            r.push(Entry {
+                path: e.path(),
+                modified: meta.modified()?,
+                size: meta.len(),
+            });
+        }
+    }
+    Ok(r)
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is stable across Rust releases.
+pub(crate) fn hash(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Cache key for everything that affects the activity found.
+pub(crate) fn key(parts: &impl serde::Serialize) -> Result<String> {
+    Ok(format!("{:016x}", hash(&serde_json::to_vec(parts)?)))
+}
@@ -0,0 +1,356 @@
+//! Exports of the parsed report into formats meant for analysis tools.
+
+use crate::RepoEventParseData;
+use anyhow::Result;
+use chrono::prelude::*;
+use serde_derive::*;
+use std::io::Write;
+
+/// A `--export` destination, written as `kind:path`.
+#[derive(Debug, Clone)]
+pub(crate) enum Export {
+    /// Append to normalized tables in a SQLite database
+    Sqlite(String),
+    /// Write one row per report item to a Parquet file
+    Parquet(String),
+    /// Write a per-project contribution summary for a CV or résumé, as
+    /// JSON if the path ends in `.json` and Markdown otherwise
+    Cv(String),
+}
+
+impl std::str::FromStr for Export {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (kind, path) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Expected kind:path, e.g. sqlite:activity.db"))?;
+        match kind {
+            "sqlite" => Ok(Self::Sqlite(path.to_string())),
+            "parquet" => Ok(Self::Parquet(path.to_string())),
+            "cv" => Ok(Self::Cv(path.to_string())),
+            _ => anyhow::bail!("Unknown export kind: {}", kind),
+        }
+    }
+}
+
+impl std::fmt::Display for Export {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Sqlite(path) => write!(f, "sqlite:{}", path),
+            Self::Parquet(path) => write!(f, "parquet:{}", path),
+            Self::Cv(path) => write!(f, "cv:{}", path),
+        }
+    }
+}
+
+impl Export {
+    pub(crate) fn write(
+        &self,
+        user: &str,
+        start: &DateTime<Local>,
+        end: &DateTime<Local>,
+        events: &RepoEventParseData,
+    ) -> Result<()> {
+        match self {
+            Self::Sqlite(path) => write_sqlite(path, user, start, end, events),
+            Self::Parquet(path) => write_parquet(path, user, events),
+            Self::Cv(path) => write_cv(path, user, start, end, events),
+        }
+    }
+}
+
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS reports (
+    id INTEGER PRIMARY KEY,
+    user TEXT NOT NULL,
+    start TEXT NOT NULL,
+    end TEXT NOT NULL,
+    generated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS repos (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS items (
+    id INTEGER PRIMARY KEY,
+    report_id INTEGER NOT NULL REFERENCES reports(id),
+    repo_id INTEGER NOT NULL REFERENCES repos(id),
+    kind TEXT NOT NULL,
+    url TEXT,
+    title TEXT NOT NULL,
+    state TEXT NOT NULL,
+    time TEXT,
+    count INTEGER NOT NULL
+);
+";
+
+/// Record this run as a new row in `reports`, along with its items.
+fn write_sqlite(
+    path: &str,
+    user: &str,
+    start: &DateTime<Local>,
+    end: &DateTime<Local>,
+    events: &RepoEventParseData,
+) -> Result<()> {
+    let mut conn = rusqlite::Connection::open(path)?;
+    conn.execute_batch(SQLITE_SCHEMA)?;
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO reports (user, start, end, generated_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![
+            user,
+            start.to_rfc3339(),
+            end.to_rfc3339(),
+            Utc::now().to_rfc3339()
+        ],
+    )?;
+    let report_id = tx.last_insert_rowid();
+    for item in events.items() {
+        tx.execute(
+            "INSERT OR IGNORE INTO repos (name) VALUES (?1)",
+            [item.repo],
+        )?;
+        let repo_id: i64 =
+            tx.query_row("SELECT id FROM repos WHERE name = ?1", [item.repo], |r| {
+                r.get(0)
+            })?;
+        tx.execute(
+            "INSERT INTO items (report_id, repo_id, kind, url, title, state, time, count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                report_id,
+                repo_id,
+                item.kind,
+                item.url,
+                item.title,
+                item.state,
+                item.time.map(|t| t.to_rfc3339()),
+                item.count
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+const PARQUET_SCHEMA: &str = "
+message item {
+    required binary user (UTF8);
+    required binary repo (UTF8);
+    required binary kind (UTF8);
+    optional binary url (UTF8);
+    required binary title (UTF8);
+    required binary state (UTF8);
+    optional int64 time (TIMESTAMP(MILLIS,true));
+    required int32 count;
+}
+";
+
+fn parquet_column<'a>(
+    rg: &'a mut parquet::file::writer::SerializedRowGroupWriter<'_, std::fs::File>,
+) -> Result<parquet::file::writer::SerializedColumnWriter<'a>> {
+    rg.next_column()?
+        .ok_or_else(|| anyhow::anyhow!("Parquet schema mismatch"))
+}
+
+/// Write a required string column.
+fn parquet_strings<'a>(
+    rg: &mut parquet::file::writer::SerializedRowGroupWriter<'_, std::fs::File>,
+    values: impl Iterator<Item = &'a str>,
+) -> Result<()> {
+    use parquet::data_type::{ByteArray, ByteArrayType};
+    let values: Vec<ByteArray> = values.map(ByteArray::from).collect();
+    let mut col = parquet_column(rg)?;
+    col.typed::<ByteArrayType>()
+        .write_batch(&values, None, None)?;
+    col.close()?;
+    Ok(())
+}
+
+/// Write the report items to `path`, replacing any existing file.
+fn write_parquet(path: &str, user: &str, events: &RepoEventParseData) -> Result<()> {
+    use parquet::data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use std::sync::Arc;
+
+    let items = events.items();
+    let schema = Arc::new(parquet::schema::parser::parse_message_type(PARQUET_SCHEMA)?);
+    let props = Arc::new(WriterProperties::builder().build());
+    let f = std::fs::File::create(path)?;
+    let mut writer = SerializedFileWriter::new(f, schema, props)?;
+    let mut rg = writer.next_row_group()?;
+    parquet_strings(&mut rg, items.iter().map(|_| user))?;
+    parquet_strings(&mut rg, items.iter().map(|i| i.repo))?;
+    parquet_strings(&mut rg, items.iter().map(|i| i.kind))?;
+    {
+        let urls: Vec<ByteArray> = items
+            .iter()
+            .filter_map(|i| i.url)
+            .map(ByteArray::from)
+            .collect();
+        let defs: Vec<i16> = items.iter().map(|i| i.url.is_some() as i16).collect();
+        let mut col = parquet_column(&mut rg)?;
+        col.typed::<ByteArrayType>()
+            .write_batch(&urls, Some(&defs), None)?;
+        col.close()?;
+    }
+    parquet_strings(&mut rg, items.iter().map(|i| i.title.as_str()))?;
+    parquet_strings(&mut rg, items.iter().map(|i| i.state.as_str()))?;
+    {
+        let times: Vec<i64> = items
+            .iter()
+            .filter_map(|i| i.time)
+            .map(|t| t.timestamp_millis())
+            .collect();
+        let defs: Vec<i16> = items.iter().map(|i| i.time.is_some() as i16).collect();
+        let mut col = parquet_column(&mut rg)?;
+        col.typed::<Int64Type>()
+            .write_batch(&times, Some(&defs), None)?;
+        col.close()?;
+    }
+    {
+        let counts: Vec<i32> = items.iter().map(|i| i.count as i32).collect();
+        let mut col = parquet_column(&mut rg)?;
+        col.typed::<Int32Type>().write_batch(&counts, None, None)?;
+        col.close()?;
+    }
+    rg.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+/// One project's entry in a contribution CV.
+#[derive(Debug, Serialize)]
+struct CvProject<'a> {
+    project: &'a str,
+    role: &'static str,
+    pull_requests_opened: usize,
+    pull_requests_merged: usize,
+    reviews: usize,
+    issues: usize,
+    pushes: u32,
+    first_contribution: bool,
+    /// Titles of the pull requests I opened
+    highlights: Vec<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Cv<'a> {
+    user: &'a str,
+    start: NaiveDate,
+    end: NaiveDate,
+    projects: Vec<CvProject<'a>>,
+}
+
+fn cv<'a>(
+    user: &'a str,
+    start: &DateTime<Local>,
+    end: &DateTime<Local>,
+    events: &'a RepoEventParseData,
+) -> Cv<'a> {
+    let mut projects: Vec<_> = events
+        .repos
+        .iter()
+        .filter(|(_, e)| e.activity() > 0 || !e.merged.is_empty())
+        .map(|(repo, e)| {
+            let owner = repo.split('/').next().unwrap_or(repo);
+            let role = if owner.eq_ignore_ascii_case(user) || !e.merged.is_empty() {
+                "Maintainer"
+            } else if !e.pr_action.is_empty() || e.pushed > 0 {
+                "Contributor"
+            } else if !e.reviewed.is_empty() {
+                "Reviewer"
+            } else {
+                "Participant"
+            };
+            let highlights = e
+                .pr_action
+                .keys()
+                .filter_map(|url| e.titles.get(url))
+                .map(|t| t.trim())
+                .collect();
+            CvProject {
+                project: repo.as_str(),
+                role,
+                pull_requests_opened: e.pr_action.len(),
+                pull_requests_merged: e.merged.len(),
+                reviews: e.reviewed.len(),
+                issues: e.issues.len(),
+                pushes: e.pushed,
+                first_contribution: e.first_contribution,
+                highlights,
+            }
+        })
+        .collect();
+    projects.sort_by_key(|p| {
+        std::cmp::Reverse(
+            p.pull_requests_opened
+                + p.pull_requests_merged
+                + p.reviews
+                + p.issues
+                + (p.pushes > 0) as usize,
+        )
+    });
+    Cv {
+        user,
+        start: start.date_naive(),
+        // Windows end at the start of the following day.
+        end: (*end - chrono::Duration::days(1)).date_naive(),
+        projects,
+    }
+}
+
+fn write_cv(
+    path: &str,
+    user: &str,
+    start: &DateTime<Local>,
+    end: &DateTime<Local>,
+    events: &RepoEventParseData,
+) -> Result<()> {
+    let cv = cv(user, start, end, events);
+    let mut w = std::io::BufWriter::new(std::fs::File::create(path)?);
+    if path.ends_with(".json") {
+        serde_json::to_writer_pretty(&mut w, &cv)?;
+        writeln!(w)?;
+        w.flush()?;
+        return Ok(());
+    }
+    writeln!(w, "# Open source contributions: {}", cv.user)?;
+    writeln!(w)?;
+    writeln!(w, "{} to {}", cv.start, cv.end)?;
+    for p in cv.projects.iter() {
+        writeln!(w)?;
+        writeln!(
+            w,
+            "## [{0}](https://github.com/{0}) — {1}",
+            p.project, p.role
+        )?;
+        writeln!(w)?;
+        let counts: Vec<String> = [
+            (p.pull_requests_opened, "pull request", " opened"),
+            (p.pull_requests_merged, "pull request", " merged"),
+            (p.reviews, "review", ""),
+            (p.issues, "issue", ""),
+            (p.pushes as usize, "push", ""),
+        ]
+        .iter()
+        .filter(|(n, _, _)| *n > 0)
+        .map(|(n, thing, what)| format!("{}{}", crate::plural(*n, thing), what))
+        .collect();
+        write!(w, "{}", counts.join(", "))?;
+        if p.first_contribution {
+            write!(w, " (first contribution)")?;
+        }
+        writeln!(w)?;
+        if !p.highlights.is_empty() {
+            writeln!(w)?;
+            for h in p.highlights.iter() {
+                writeln!(w, "- {}", h)?;
+            }
+        }
+    }
+    w.flush()?;
+    Ok(())
+}
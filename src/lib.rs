@@ -0,0 +1,5831 @@
+use anyhow::Result;
+use chrono::prelude::*;
+use serde_derive::*;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{IsTerminal, Write};
+use structopt::StructOpt;
+
+mod cache;
+mod export;
+mod mcp;
+mod tui;
+
+/// Maximum page index we will request from the events API.
+const PAGE_LIMIT: u32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Actor {
+    pub id: u64,
+    pub login: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Review {
+    pub pull_request_url: String,
+    pub submitted_at: chrono::DateTime<Utc>,
+    pub state: String,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Label {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Milestone {
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PullRequest {
+    pub url: String,
+    pub html_url: String,
+    pub title: String,
+    pub user: Option<Actor>,
+    pub author_association: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    pub milestone: Option<Milestone>,
+    pub body: Option<String>,
+    pub merged: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Comment {
+    pub url: String,
+    pub html_url: String,
+    pub issue_url: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Issue {
+    pub url: String,
+    pub title: String,
+    pub html_url: String,
+    pub user: Option<Actor>,
+    pub author_association: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    pub milestone: Option<Milestone>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CommitAuthor {
+    pub email: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Commit {
+    pub sha: String,
+    pub message: String,
+    pub author: CommitAuthor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Payload {
+    pub action: Option<String>,
+    pub review: Option<Review>,
+    pub pull_request: Option<PullRequest>,
+    pub issue: Option<Issue>,
+    pub comment: Option<Comment>,
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+    pub commits: Option<Vec<Commit>>,
+}
+
+impl Event {
+    /// Labels of the pull request or issue this event is about, if any.
+    fn labels(&self) -> Option<&[Label]> {
+        self.payload
+            .pull_request
+            .as_ref()
+            .map(|pr| pr.labels.as_slice())
+            .or_else(|| self.payload.issue.as_ref().map(|i| i.labels.as_slice()))
+    }
+
+    /// URL of the pull request or issue this event is about, if any.
+    fn item_url(&self) -> Option<&str> {
+        self.payload
+            .pull_request
+            .as_ref()
+            .map(|pr| pr.html_url.as_str())
+            .or_else(|| self.payload.issue.as_ref().map(|i| i.html_url.as_str()))
+    }
+
+    /// Milestone of the pull request or issue this event is about, if any.
+    fn milestone(&self) -> Option<&Milestone> {
+        self.payload
+            .pull_request
+            .as_ref()
+            .and_then(|pr| pr.milestone.as_ref())
+            .or_else(|| {
+                self.payload
+                    .issue
+                    .as_ref()
+                    .and_then(|i| i.milestone.as_ref())
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Repo {
+    pub id: u64,
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct Event {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub typ: String,
+    pub actor: Actor,
+    pub repo: Repo,
+    pub payload: Payload,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+/// Main options struct
+///
+/// Every option can also be set via a `MYGHSTATUS_`-prefixed environment
+/// variable, which is convenient for cron jobs and containers.
+struct Opt {
+    #[structopt(long, default_value = "0", env = "MYGHSTATUS_PREVIOUS_DAY")]
+    previous_day: u32,
+    #[structopt(long, env = "MYGHSTATUS_USER")]
+    user: String,
+    /// Other logins (e.g. old usernames) whose activity should be reported as mine
+    #[structopt(long, env = "MYGHSTATUS_ALSO_LOGIN", use_delimiter = true)]
+    also_login: Vec<String>,
+    /// My commit author emails, used to find commits of mine pushed by others
+    #[structopt(long, env = "MYGHSTATUS_EMAIL", use_delimiter = true)]
+    email: Vec<String>,
+    /// Repositories (`org/repo`) whose pushes by others are checked for commits
+    /// authored with one of --email
+    #[structopt(long, env = "MYGHSTATUS_PUSH_REPO", use_delimiter = true)]
+    push_repo: Vec<String>,
+    #[structopt(long, env = "MYGHSTATUS_FROM_FILE")]
+    from_file: Option<String>,
+    /// Only report pull requests and issues with one of these labels
+    #[structopt(long, env = "MYGHSTATUS_LABEL_FILTER", use_delimiter = true)]
+    label_filter: Vec<String>,
+    /// Don't report pull requests and issues with any of these labels
+    #[structopt(long, env = "MYGHSTATUS_EXCLUDE_LABEL", use_delimiter = true)]
+    exclude_label: Vec<String>,
+    /// Format dates, times and counts for this locale (e.g. `fr_FR`, `de_DE`, `ja_JP`)
+    #[structopt(long, env = "MYGHSTATUS_LOCALE", parse(try_from_str = parse_locale))]
+    locale: Option<chrono::Locale>,
+    /// Local time (`HH:MM`) at which each report day starts; activity before
+    /// it counts towards the previous day
+    #[structopt(
+        long,
+        default_value = "06:00",
+        env = "MYGHSTATUS_DAY_START",
+        parse(try_from_str = parse_day_start)
+    )]
+    day_start: NaiveTime,
+    /// Pretend it is this time (RFC 3339, e.g. `2024-09-10T09:00:00+02:00`)
+    /// when computing the report window
+    #[structopt(long, env = "MYGHSTATUS_NOW")]
+    now: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// Compute the report window as if run on this date (`YYYY-MM-DD`)
+    #[structopt(long, env = "MYGHSTATUS_AS_OF", conflicts_with = "now")]
+    as_of: Option<NaiveDate>,
+    /// First day of sprint 1, used with --sprint
+    #[structopt(long, env = "MYGHSTATUS_SPRINT_START")]
+    sprint_start: Option<NaiveDate>,
+    /// Sprint length in days
+    #[structopt(long, default_value = "14", env = "MYGHSTATUS_SPRINT_LENGTH")]
+    sprint_length: u32,
+    /// Report on a sprint instead of the previous day: `current`, `previous` or a sprint number
+    #[structopt(long, env = "MYGHSTATUS_SPRINT")]
+    sprint: Option<Sprint>,
+    /// Days I was out of office, as `YYYY-MM-DD` or `YYYY-MM-DD..YYYY-MM-DD`
+    /// (inclusive); these are skipped when finding the previous workday
+    #[structopt(long, env = "MYGHSTATUS_ABSENCE", use_delimiter = true)]
+    absence: Vec<DateRange>,
+    /// Produce one report per window (`YYYY-MM-DD..YYYY-MM-DD`, inclusive)
+    /// instead of one for the previous day, e.g. to catch up on missed standups
+    #[structopt(long, env = "MYGHSTATUS_WINDOW", use_delimiter = true)]
+    window: Vec<DateRange>,
+    /// Report from this day (`YYYY-MM-DD`) or time (RFC 3339) instead of
+    /// the previous workday
+    #[structopt(long, env = "MYGHSTATUS_SINCE", conflicts_with_all = &["window", "sprint"])]
+    since: Option<Boundary>,
+    /// With --since, report up to and including this day, or up to this
+    /// time; defaults to now
+    #[structopt(long, env = "MYGHSTATUS_UNTIL", requires = "since")]
+    until: Option<Boundary>,
+    /// Report on the last 7 days, or on an ISO week (`YYYY-Www`), instead of
+    /// the previous workday
+    #[structopt(long, env = "MYGHSTATUS_WEEK", conflicts_with_all = &["window", "sprint", "since"])]
+    week: Option<Option<IsoWeek>>,
+    /// Report on a calendar month (`YYYY-MM`). The event feed only reaches
+    /// back 90 days and 300 events, so use --search-backfill for busy or
+    /// older months
+    #[structopt(long, env = "MYGHSTATUS_MONTH", conflicts_with_all = &["window", "sprint", "since", "week"])]
+    month: Option<Month>,
+    /// Report on a calendar quarter (`Q1-2024`), summarized per repository
+    /// as with --digest; see --month about older activity
+    #[structopt(long, env = "MYGHSTATUS_QUARTER", conflicts_with_all = &["window", "sprint", "since", "week", "month"])]
+    quarter: Option<Quarter>,
+    /// Only report pull requests and issues attached to this milestone
+    #[structopt(long, env = "MYGHSTATUS_MILESTONE")]
+    milestone: Option<String>,
+    /// Print the API requests and output targets that would be used, then exit
+    #[structopt(long)]
+    dry_run: bool,
+    /// Reuse activity fetched for the same query within this many seconds
+    #[structopt(long, default_value = "300", env = "MYGHSTATUS_CACHE_TTL")]
+    cache_ttl: u64,
+    /// Always fetch activity instead of using the cache
+    #[structopt(long)]
+    no_cache: bool,
+    /// Write JSON metadata about this run (window, pages, counts, timing) to this path
+    #[structopt(long, env = "MYGHSTATUS_EMIT_META")]
+    emit_meta: Option<String>,
+    /// Write the report to this path instead of stdout; `{date}`, `{user}`
+    /// and `{period}` are expanded, e.g. `reports/{date}-{user}.md`
+    #[structopt(long, env = "MYGHSTATUS_OUTPUT")]
+    output: Option<String>,
+    /// Append to the --output and --split-by-repo files instead of replacing them
+    #[structopt(long)]
+    append: bool,
+    /// Output format: `markdown`, `md-table`, `narrative`, `text`, `terminal`,
+    /// `asciidoc`, `json`, `yaml`, `csv`, `html`, `html-fragment` (just the
+    /// page body), `discord`, `atom`, `email`, `waybar`, `confluence`,
+    /// `quickfix` or `mermaid`.
+    /// Defaults to `terminal` when stdout is a terminal (unless `NO_COLOR`
+    /// is set), and `markdown` otherwise
+    #[structopt(long, env = "MYGHSTATUS_FORMAT")]
+    format: Option<Format>,
+    /// When the event feed doesn't reach back to the window start, fill in
+    /// pull requests and comments from the search API
+    #[structopt(long)]
+    search_backfill: bool,
+    /// Include the first line of each review body under the reviewed pull request
+    #[structopt(long)]
+    show_review_bodies: bool,
+    /// Include the first line of each issue comment under the issue
+    #[structopt(long)]
+    show_comment_excerpts: bool,
+    /// Look up the current name of each repository, merging activity
+    /// recorded under names from before a rename or transfer
+    #[structopt(long)]
+    resolve_renames: bool,
+    /// Style of `--format html` and `email` pages: `light`, `dark`, or
+    /// `auto` to follow the reader's system setting
+    #[structopt(long, default_value = "light", env = "MYGHSTATUS_HTML_THEME")]
+    html_theme: HtmlTheme,
+    /// Add this CSS file to `--format html` and `email` pages, after the theme
+    #[structopt(long, env = "MYGHSTATUS_HTML_CSS")]
+    html_css: Option<String>,
+    /// Leave emoji out of `--format text` output
+    #[structopt(long)]
+    no_emoji: bool,
+    /// Use text badges instead of emoji in `--format html` output
+    #[structopt(long)]
+    no_emoji_html: bool,
+    /// Browse the activity in a terminal UI, picking which items go into
+    /// the report before it is rendered
+    #[structopt(long)]
+    tui: bool,
+    /// Show a desktop notification with a one-line summary when done;
+    /// clicking it opens the report written to --output
+    #[structopt(long)]
+    notify: bool,
+    /// Prefix the report with a metadata block: `yaml` for the report's
+    /// totals, or `hugo` for a blog post's title, date and tags (also
+    /// understood by Jekyll)
+    #[structopt(long, env = "MYGHSTATUS_FRONT_MATTER")]
+    front_matter: Option<FrontMatter>,
+    /// Collapse each repository into a single summary line
+    #[structopt(long)]
+    digest: bool,
+    /// Attribute activity to a workstream or objective, as `NAME=org/repo`,
+    /// `NAME=label:LABEL` or `NAME=title:REGEX`; repeat for more rules.
+    /// Items go to the first objective with a matching rule
+    #[structopt(long, env = "MYGHSTATUS_OKR")]
+    okr: Vec<OkrRule>,
+    /// Show the share of activity attributed to each --okr objective
+    /// instead of the report
+    #[structopt(long)]
+    by_okr: bool,
+    /// Show at most N entries per section, followed by a count of the rest
+    #[structopt(long, env = "MYGHSTATUS_MAX_ITEMS")]
+    max_items: Option<usize>,
+    /// Wrap each repository section in a collapsible `<details>` block
+    #[structopt(long)]
+    collapsible: bool,
+    /// Wrap each category (pull requests, reviews, ...) in a collapsible
+    /// `<details>` block
+    #[structopt(long)]
+    collapsible_categories: bool,
+    /// With --collapsible, keep the N most active repositories expanded
+    #[structopt(long, env = "MYGHSTATUS_COLLAPSIBLE_TOP")]
+    collapsible_top: Option<usize>,
+    /// Write shields.io endpoint badges (JSON) for the report totals into this directory
+    #[structopt(long, env = "MYGHSTATUS_BADGE_DIR")]
+    badge_dir: Option<String>,
+    /// Also write each repository's section as Markdown to `<dir>/<owner>/<repo>.md`
+    #[structopt(long, env = "MYGHSTATUS_SPLIT_BY_REPO")]
+    split_by_repo: Option<String>,
+    /// Replace the section between `<!-- mygithubstatus:start -->` and
+    /// `<!-- mygithubstatus:end -->` in the user's profile README with this report
+    #[structopt(long)]
+    update_profile_readme: bool,
+    /// Split the report into "My projects" and "Contributions elsewhere"
+    #[structopt(long)]
+    split_ownership: bool,
+    /// Owners (`org`) or repositories (`org/repo`) to count as my projects,
+    /// in addition to those owned by --user
+    #[structopt(long, env = "MYGHSTATUS_MAINTAIN", use_delimiter = true)]
+    maintain: Vec<String>,
+    /// Use the search API to flag repositories where this is my first contribution
+    #[structopt(long)]
+    detect_first_contributions: bool,
+    /// Use the issue timeline API to mark issues I only commented on that
+    /// were closed later in the window as "since resolved"
+    #[structopt(long)]
+    check_resolved: bool,
+    /// Pipe the report as JSON to this shell command and prepend its output as a summary
+    #[structopt(long, env = "MYGHSTATUS_SUMMARIZE_CMD")]
+    summarize_cmd: Option<String>,
+    /// Sender for `--format email`; left to the mail transfer agent if unset
+    #[structopt(long, env = "MYGHSTATUS_EMAIL_FROM")]
+    email_from: Option<String>,
+    /// Recipients for `--format email`, as needed by `sendmail -t`
+    #[structopt(long, env = "MYGHSTATUS_EMAIL_TO", use_delimiter = true)]
+    email_to: Vec<String>,
+    /// Render the report with this Handlebars template instead of `--format`;
+    /// it receives the same model as `--format json`
+    #[structopt(long, env = "MYGHSTATUS_TEMPLATE")]
+    template: Option<String>,
+    /// Sign the report with this shell command, which must read the report on
+    /// stdin and write a detached signature to stdout (e.g. `gpg --detach-sign --armor`).
+    /// The signature is written next to --output as `.sig`, or appended on stdout.
+    #[structopt(long, env = "MYGHSTATUS_SIGN")]
+    sign: Option<String>,
+    /// Write activity-by-day and activity-by-repository bar charts to this SVG file
+    #[structopt(long, env = "MYGHSTATUS_CHART_FILE")]
+    chart_file: Option<String>,
+    /// Write a contribution-graph style heatmap of activity per day to this SVG file
+    #[structopt(long, env = "MYGHSTATUS_HEATMAP_FILE")]
+    heatmap_file: Option<String>,
+    /// Publish the report to this Confluence instance (e.g. `https://example.atlassian.net/wiki`);
+    /// authenticates with CONFLUENCE_TOKEN, plus CONFLUENCE_USER for basic auth
+    #[structopt(long, env = "MYGHSTATUS_CONFLUENCE_URL")]
+    confluence_url: Option<String>,
+    /// Confluence space key to publish into
+    #[structopt(long, env = "MYGHSTATUS_CONFLUENCE_SPACE")]
+    confluence_space: Option<String>,
+    /// Confluence page title; supports the same variables as --output
+    #[structopt(
+        long,
+        default_value = "Status for {user} {date}",
+        env = "MYGHSTATUS_CONFLUENCE_TITLE"
+    )]
+    confluence_title: String,
+    /// Export the parsed report, e.g. `sqlite:activity.db`, `parquet:items.parquet`
+    /// or `cv:contributions.md` (or `.json`) for a per-project summary over
+    /// the window; may be repeated
+    #[structopt(long, env = "MYGHSTATUS_EXPORT")]
+    export: Vec<export::Export>,
+    /// Print a breakdown of where time was spent to stderr
+    #[structopt(long)]
+    timings: bool,
+    #[structopt(subcommand)]
+    cmd: Option<Command>,
+}
+
+#[derive(Debug, Clone, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+enum Command {
+    /// Statistics over the report window instead of the report itself
+    Stats(StatsCommand),
+    /// A one-line summary for shell prompts and status bars; served from
+    /// the cache when possible
+    Brief,
+    /// Serve reports as Model Context Protocol tools over stdio
+    Mcp,
+    /// Manage the on-disk cache of fetched activity
+    Cache(CacheCommand),
+    /// Print a systemd user service and timer that run this report every weekday morning
+    SystemdUnit,
+}
+
+#[derive(Debug, Clone, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+enum CacheCommand {
+    /// Print where the cache lives
+    Path,
+    /// Show the number of entries and their total size
+    Status,
+    /// Remove entries older than --cache-ttl
+    Prune,
+    /// Remove every entry
+    Clear,
+}
+
+#[derive(Debug, Clone, StructOpt)]
+#[structopt(rename_all = "kebab-case")]
+enum StatsCommand {
+    /// Most frequent terms in pull request and issue titles, per repository
+    Topics {
+        /// Number of terms to show per repository
+        #[structopt(long, default_value = "5")]
+        top: usize,
+    },
+    /// Activity by others on my repositories: pull requests, issues, contributors
+    Received {
+        /// Repositories to check (`org/repo`); defaults to my own repositories
+        /// plus those listed in --maintain
+        #[structopt(long)]
+        repo: Vec<String>,
+    },
+    /// Median time from an issue or pull request being opened to my first response
+    ResponseTime {
+        /// Repository to check (`org/repo`)
+        #[structopt(long)]
+        repo: String,
+    },
+}
+
+/// An output format, as named by `--format`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Markdown,
+    MdTable,
+    Narrative,
+    /// Plain text for pasting where Markdown isn't rendered
+    Text,
+    /// Colored text with clickable links; the default instead of
+    /// `markdown` when stdout is a terminal
+    Terminal,
+    AsciiDoc,
+    /// The parsed events, for jq and other tooling
+    Json,
+    /// The same data as `json`, as YAML
+    Yaml,
+    /// One row per report item
+    Csv,
+    /// A standalone HTML page
+    Html,
+    /// The body of an `html` page, for embedding
+    HtmlFragment,
+    /// A Discord webhook payload with one embed field per repository
+    Discord,
+    /// An Atom feed with an entry per report item
+    Atom,
+    /// An email message with plain text and HTML parts, for piping into sendmail
+    Email,
+    /// JSON for a Waybar custom module: counts as text, the report as tooltip
+    Waybar,
+    /// Confluence storage format (XHTML), as published by --confluence-url
+    Confluence,
+    /// Items that may need follow-up, one `URL message` line each, for
+    /// editor quickfix lists
+    Quickfix,
+    /// A Mermaid timeline of the day's items, in a Markdown code block
+    Mermaid,
+}
+
+impl Format {
+    /// Formats that make up a complete document by themselves, without the
+    /// usual header lines or front matter.
+    fn is_standalone(self) -> bool {
+        matches!(
+            self,
+            Self::Json
+                | Self::Yaml
+                | Self::Csv
+                | Self::Html
+                | Self::HtmlFragment
+                | Self::Discord
+                | Self::Atom
+                | Self::Email
+                | Self::Waybar
+                | Self::Confluence
+                | Self::Quickfix
+                | Self::Mermaid
+        )
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "markdown" => Ok(Self::Markdown),
+            "md-table" => Ok(Self::MdTable),
+            "narrative" => Ok(Self::Narrative),
+            "text" => Ok(Self::Text),
+            "terminal" => Ok(Self::Terminal),
+            "asciidoc" => Ok(Self::AsciiDoc),
+            "json" => Ok(Self::Json),
+            "yaml" => Ok(Self::Yaml),
+            "csv" => Ok(Self::Csv),
+            "html" => Ok(Self::Html),
+            "html-fragment" => Ok(Self::HtmlFragment),
+            "discord" => Ok(Self::Discord),
+            "atom" => Ok(Self::Atom),
+            "email" => Ok(Self::Email),
+            "waybar" => Ok(Self::Waybar),
+            "confluence" => Ok(Self::Confluence),
+            "quickfix" => Ok(Self::Quickfix),
+            "mermaid" => Ok(Self::Mermaid),
+            _ => anyhow::bail!("Unknown format: {}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Sprint {
+    Current,
+    Previous,
+    /// 1-based sprint number counted from --sprint-start
+    Number(u32),
+}
+
+impl std::str::FromStr for Sprint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "current" => Ok(Self::Current),
+            "previous" => Ok(Self::Previous),
+            n => match n.parse() {
+                Ok(n) if n > 0 => Ok(Self::Number(n)),
+                _ => anyhow::bail!("Invalid sprint: {}", s),
+            },
+        }
+    }
+}
+
+/// What an --okr rule matches on.
+#[derive(Debug, Clone)]
+enum OkrPattern {
+    /// An owner (`org`) or repository (`org/repo`)
+    Repo(String),
+    Label(String),
+    Title(regex::Regex),
+}
+
+/// A workstream or objective and the activity attributed to it,
+/// `NAME=org/repo`, `NAME=label:LABEL` or `NAME=title:REGEX`.
+#[derive(Debug, Clone)]
+struct OkrRule {
+    name: String,
+    pattern: OkrPattern,
+}
+
+impl OkrRule {
+    fn matches(&self, repo: &str, events: &RepoEvents, url: Option<&str>) -> bool {
+        let labels = url.and_then(|u| events.labels.get(u));
+        let title = url.and_then(|u| events.titles.get(u));
+        match self.pattern {
+            OkrPattern::Repo(ref r) => RenderOptions::is_mine(std::slice::from_ref(r), repo),
+            OkrPattern::Label(ref l) => labels.map(|v| v.contains(l)).unwrap_or(false),
+            OkrPattern::Title(ref re) => title.map(|t| re.is_match(t)).unwrap_or(false),
+        }
+    }
+}
+
+impl std::str::FromStr for OkrRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, pattern) = match s.split_once('=') {
+            Some((n, p)) if !n.is_empty() && !p.is_empty() => (n, p),
+            _ => anyhow::bail!("Invalid OKR rule (expected NAME=PATTERN): {}", s),
+        };
+        let pattern = if let Some(l) = pattern.strip_prefix("label:") {
+            OkrPattern::Label(l.to_string())
+        } else if let Some(re) = pattern.strip_prefix("title:") {
+            OkrPattern::Title(
+                regex::RegexBuilder::new(re)
+                    .case_insensitive(true)
+                    .build()?,
+            )
+        } else {
+            OkrPattern::Repo(pattern.to_string())
+        };
+        Ok(Self {
+            name: name.to_string(),
+            pattern,
+        })
+    }
+}
+
+/// An inclusive range of days, `YYYY-MM-DD` or `YYYY-MM-DD..YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy)]
+struct DateRange {
+    first: NaiveDate,
+    last: NaiveDate,
+}
+
+impl DateRange {
+    fn contains(&self, day: NaiveDate) -> bool {
+        self.first <= day && day <= self.last
+    }
+}
+
+impl std::str::FromStr for DateRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (first, last) = match s.split_once("..") {
+            Some((a, b)) => (a.parse()?, b.parse()?),
+            None => {
+                let d = s.parse()?;
+                (d, d)
+            }
+        };
+        if last < first {
+            anyhow::bail!("Date range ends before it starts: {}", s);
+        }
+        Ok(Self { first, last })
+    }
+}
+
+/// An ISO week, `YYYY-Www` (e.g. `2024-W37`).
+#[derive(Debug, Clone, Copy)]
+struct IsoWeek {
+    monday: NaiveDate,
+}
+
+impl std::str::FromStr for IsoWeek {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("Expected an ISO week like 2024-W37: {}", s);
+        let (year, week) = s.split_once("-W").ok_or_else(invalid)?;
+        let year = year.parse().map_err(|_| invalid())?;
+        let week = week.parse().map_err(|_| invalid())?;
+        let monday =
+            NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon).ok_or_else(invalid)?;
+        Ok(Self { monday })
+    }
+}
+
+/// A calendar month, `YYYY-MM`.
+#[derive(Debug, Clone, Copy)]
+struct Month {
+    first: NaiveDate,
+}
+
+impl Month {
+    fn next(&self) -> NaiveDate {
+        self.first
+            .checked_add_months(chrono::Months::new(1))
+            .expect("valid date")
+    }
+}
+
+impl std::str::FromStr for Month {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("Expected a month like 2024-04: {}", s);
+        let (year, month) = s.split_once('-').ok_or_else(invalid)?;
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid)?;
+        Ok(Self { first })
+    }
+}
+
+/// A calendar quarter, `Q1-2024` or `2024-Q1`.
+#[derive(Debug, Clone, Copy)]
+struct Quarter {
+    first: NaiveDate,
+}
+
+impl Quarter {
+    fn next(&self) -> NaiveDate {
+        self.first
+            .checked_add_months(chrono::Months::new(3))
+            .expect("valid date")
+    }
+}
+
+impl std::str::FromStr for Quarter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("Expected a quarter like Q1-2024: {}", s);
+        let (q, year) = match s.split_once('-').ok_or_else(invalid)? {
+            (a, b) if a.starts_with('Q') => (a, b),
+            (a, b) => (b, a),
+        };
+        let q: u32 = q
+            .strip_prefix('Q')
+            .ok_or_else(invalid)?
+            .parse()
+            .map_err(|_| invalid())?;
+        let year = year.parse().map_err(|_| invalid())?;
+        if !(1..=4).contains(&q) {
+            return Err(invalid());
+        }
+        let first = NaiveDate::from_ymd_opt(year, (q - 1) * 3 + 1, 1).ok_or_else(invalid)?;
+        Ok(Self { first })
+    }
+}
+
+/// One end of a --since/--until range: a day (`YYYY-MM-DD`) or an exact
+/// time (RFC 3339).
+#[derive(Debug, Clone, Copy)]
+enum Boundary {
+    Day(NaiveDate),
+    Time(chrono::DateTime<chrono::FixedOffset>),
+}
+
+impl Boundary {
+    /// When the range starts, if this is its first day.
+    fn start(&self, at: NaiveTime) -> chrono::DateTime<Local> {
+        match self {
+            Self::Day(d) => day_start(*d, at),
+            Self::Time(t) => t.with_timezone(&Local),
+        }
+    }
+
+    /// When the range ends, if this is its last day.
+    fn end(&self, at: NaiveTime) -> chrono::DateTime<Local> {
+        match self {
+            Self::Day(d) => day_start(d.succ_opt().expect("valid date"), at),
+            Self::Time(t) => t.with_timezone(&Local),
+        }
+    }
+}
+
+impl std::str::FromStr for Boundary {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(t) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Ok(Self::Time(t));
+        }
+        s.parse()
+            .map(Self::Day)
+            .map_err(|_| anyhow::anyhow!("Expected YYYY-MM-DD or an RFC 3339 time: {}", s))
+    }
+}
+
+/// Source of the current time, so runs can be simulated with --now.
+trait Clock {
+    fn now(&self) -> chrono::DateTime<Local>;
+
+    fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+}
+
+struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> chrono::DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A clock stopped at a fixed time.
+struct FixedClock(chrono::DateTime<Local>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> chrono::DateTime<Local> {
+        self.0
+    }
+}
+
+type Span = (chrono::DateTime<Local>, chrono::DateTime<Local>);
+
+/// The windows to report on as of `clock`, and what to call them in
+/// output templates.
+fn report_windows(opt: &Opt, clock: &dyn Clock) -> Result<(Vec<Span>, &'static str)> {
+    // From the start of report day `first` to the start of `end`.
+    let span = |first, end| {
+        (
+            day_start(first, opt.day_start),
+            day_start(end, opt.day_start),
+        )
+    };
+    Ok(if !opt.window.is_empty() {
+        let windows = opt
+            .window
+            .iter()
+            .map(|r| span(r.first, r.last.succ_opt().expect("valid date")))
+            .collect();
+        (windows, "window")
+    } else if let Some(week) = opt.week {
+        let first = match week {
+            Some(w) => w.monday,
+            None => clock.today() - chrono::Duration::days(7),
+        };
+        (vec![span(first, first + chrono::Duration::days(7))], "week")
+    } else if let Some(month) = opt.month {
+        (vec![span(month.first, month.next())], "month")
+    } else if let Some(quarter) = opt.quarter {
+        (vec![span(quarter.first, quarter.next())], "quarter")
+    } else if let Some(since) = opt.since {
+        let start = since.start(opt.day_start);
+        let now = || chrono::SubsecRound::trunc_subsecs(clock.now(), 0);
+        let end = opt.until.map(|u| u.end(opt.day_start)).unwrap_or_else(now);
+        if end <= start {
+            anyhow::bail!("--until must be after --since");
+        }
+        (vec![(start, end)], "window")
+    } else if let Some(sprint) = opt.sprint {
+        let first = opt
+            .sprint_start
+            .ok_or_else(|| anyhow::anyhow!("--sprint requires --sprint-start"))?;
+        let today = clock.today();
+        let (start, end) = sprint_window(sprint, first, opt.sprint_length, today, opt.day_start)?;
+        (vec![(start, end)], "sprint")
+    } else {
+        let day = clock.today() - chrono::Duration::days(opt.previous_day as i64);
+        let start = previous_workday(day, &opt.absence);
+        (vec![span(start, day)], "day")
+    })
+}
+
+/// A report window and where its report goes.
+struct Window {
+    start: chrono::DateTime<Local>,
+    end: chrono::DateTime<Local>,
+    output: Option<String>,
+}
+
+fn is_workday(day: NaiveDate, absences: &[DateRange]) -> bool {
+    !matches!(day.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun)
+        && !absences.iter().any(|a| a.contains(day))
+}
+
+/// The last workday before `day`, skipping weekends and absences.
+fn previous_workday(day: NaiveDate, absences: &[DateRange]) -> NaiveDate {
+    let mut prev = day.pred_opt().expect("valid date");
+    while !is_workday(prev, absences) {
+        prev = prev.pred_opt().expect("valid date");
+    }
+    prev
+}
+
+/// Describe the weekdays in `[first, end)` I was out, e.g. `(out of office Tue–Wed)`.
+fn absence_note(first: NaiveDate, end: NaiveDate, absences: &[DateRange]) -> Option<String> {
+    let mut runs: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+    let mut day = first;
+    while day < end {
+        if is_workday(day, &[]) && !is_workday(day, absences) {
+            // Runs of absent days continue across weekends
+            match runs.last_mut() {
+                Some(run)
+                    if run
+                        .1
+                        .iter_days()
+                        .skip(1)
+                        .take_while(|&d| d < day)
+                        .all(|d| !is_workday(d, &[])) =>
+                {
+                    run.1 = day
+                }
+                _ => runs.push((day, day)),
+            }
+        }
+        day = day.succ_opt().expect("valid date");
+    }
+    if runs.is_empty() {
+        return None;
+    }
+    let runs: Vec<String> = runs
+        .iter()
+        .map(|&(a, b)| {
+            if a == b {
+                a.format("%a").to_string()
+            } else {
+                format!("{}–{}", a.format("%a"), b.format("%a"))
+            }
+        })
+        .collect();
+    Some(format!("(out of office {})", runs.join(", ")))
+}
+
+/// The (local) start of the report day `day`, i.e. `at` (--day-start) on that date.
+///
+/// Around DST transitions the local time may be ambiguous, in which case the
+/// earlier instant is used, or skipped entirely, in which case the window
+/// starts at the first valid local time after it.
+fn day_start(day: NaiveDate, at: NaiveTime) -> chrono::DateTime<Local> {
+    let mut t = day.and_time(at);
+    loop {
+        match Local.from_local_datetime(&t) {
+            chrono::LocalResult::Single(t) => return t,
+            chrono::LocalResult::Ambiguous(earlier, _) => return earlier,
+            // No transition skips more than a day; step past the gap.
+            chrono::LocalResult::None => t += chrono::Duration::minutes(15),
+        }
+    }
+}
+
+/// The window covered by `sprint`, given the first day of sprint 1.
+fn sprint_window(
+    sprint: Sprint,
+    first: NaiveDate,
+    length: u32,
+    today: NaiveDate,
+    at: NaiveTime,
+) -> Result<(chrono::DateTime<Local>, chrono::DateTime<Local>)> {
+    if length == 0 {
+        anyhow::bail!("Sprint length must be positive");
+    }
+    let current = (today - first).num_days().div_euclid(length as i64);
+    let index = match sprint {
+        Sprint::Current => current,
+        Sprint::Previous => current - 1,
+        Sprint::Number(n) => n as i64 - 1,
+    };
+    let add = |day: NaiveDate, days: Option<i64>| {
+        days.and_then(chrono::Duration::try_days)
+            .and_then(|d| day.checked_add_signed(d))
+            .ok_or_else(|| anyhow::anyhow!("Sprint out of range"))
+    };
+    let start = add(first, index.checked_mul(length as i64))?;
+    let end = add(start, Some(length as i64))?;
+    Ok((day_start(start, at), day_start(end, at)))
+}
+
+/// Built-in styles for `--format html` pages.
+#[derive(Debug, Clone, Copy)]
+enum HtmlTheme {
+    Light,
+    Dark,
+    /// Follow the reader's `prefers-color-scheme`
+    Auto,
+}
+
+impl std::str::FromStr for HtmlTheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "light" => Ok(Self::Light),
+            "dark" => Ok(Self::Dark),
+            "auto" => Ok(Self::Auto),
+            _ => anyhow::bail!("Unknown HTML theme: {}", s),
+        }
+    }
+}
+
+impl HtmlTheme {
+    // Both pairs of badge colors have a contrast ratio well above WCAG AA.
+    const LIGHT: &'static str =
+        ".badge { color: #000; background: #e8e8e8; padding: 0 .3em; border-radius: .3em; }";
+    const DARK: &'static str = "body { color: #e6edf3; background: #0d1117; } \
+a { color: #4493f8; } \
+.badge { color: #fff; background: #30363d; padding: 0 .3em; border-radius: .3em; }";
+
+    fn css(&self) -> String {
+        match self {
+            Self::Light => Self::LIGHT.to_string(),
+            Self::Dark => Self::DARK.to_string(),
+            Self::Auto => format!(
+                "{}\n@media (prefers-color-scheme: dark) {{ {} }}",
+                Self::LIGHT,
+                Self::DARK
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FrontMatter {
+    Yaml,
+    Hugo,
+}
+
+impl std::str::FromStr for FrontMatter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "yaml" => Ok(Self::Yaml),
+            "hugo" => Ok(Self::Hugo),
+            _ => anyhow::bail!("Unknown front matter format: {}", s),
+        }
+    }
+}
+
+/// Whether a boolean environment override like `MYGHSTATUS_DRY_RUN=1` is set.
+fn env_flag(name: &str) -> bool {
+    match std::env::var(name) {
+        Ok(v) => !matches!(v.as_str(), "" | "0" | "false" | "no"),
+        Err(_) => false,
+    }
+}
+
+impl Opt {
+    /// The main user plus any --also-login aliases.
+    fn logins(&self) -> Vec<&str> {
+        std::iter::once(self.user.as_str())
+            .chain(self.also_login.iter().map(|s| s.as_str()))
+            .collect()
+    }
+
+    /// Whether the report is a complete document of its own, without the
+    /// usual header lines.
+    fn is_standalone(&self) -> bool {
+        self.template.is_some() || self.format().is_standalone()
+    }
+
+    /// The requested output format, Markdown if none was given.
+    fn format(&self) -> Format {
+        self.format.unwrap_or(Format::Markdown)
+    }
+
+    /// clap 2 doesn't support `env` for flags, so handle those by hand.
+    fn apply_env_flags(&mut self) {
+        self.dry_run |= env_flag("MYGHSTATUS_DRY_RUN");
+        self.timings |= env_flag("MYGHSTATUS_TIMINGS");
+        self.collapsible |= env_flag("MYGHSTATUS_COLLAPSIBLE");
+        self.by_okr |= env_flag("MYGHSTATUS_BY_OKR");
+        self.collapsible_categories |= env_flag("MYGHSTATUS_COLLAPSIBLE_CATEGORIES");
+        self.split_ownership |= env_flag("MYGHSTATUS_SPLIT_OWNERSHIP");
+        self.detect_first_contributions |= env_flag("MYGHSTATUS_DETECT_FIRST_CONTRIBUTIONS");
+        self.check_resolved |= env_flag("MYGHSTATUS_CHECK_RESOLVED");
+        self.update_profile_readme |= env_flag("MYGHSTATUS_UPDATE_PROFILE_README");
+        self.no_cache |= env_flag("MYGHSTATUS_NO_CACHE");
+        self.no_emoji |= env_flag("MYGHSTATUS_NO_EMOJI");
+        self.no_emoji_html |= env_flag("MYGHSTATUS_NO_EMOJI_HTML");
+        self.tui |= env_flag("MYGHSTATUS_TUI");
+        self.notify |= env_flag("MYGHSTATUS_NOTIFY");
+        self.digest |= env_flag("MYGHSTATUS_DIGEST");
+        self.append |= env_flag("MYGHSTATUS_APPEND");
+        self.search_backfill |= env_flag("MYGHSTATUS_SEARCH_BACKFILL");
+        self.show_review_bodies |= env_flag("MYGHSTATUS_SHOW_REVIEW_BODIES");
+        self.show_comment_excerpts |= env_flag("MYGHSTATUS_SHOW_COMMENT_EXCERPTS");
+        self.resolve_renames |= env_flag("MYGHSTATUS_RESOLVE_RENAMES");
+    }
+}
+
+/// The request `query()` would issue, for display purposes.
+fn describe_query(user: &str, page: u32) -> String {
+    format!(
+        "GET https://api.github.com/users/{}/events/public?page={}",
+        urlencoding::encode(user),
+        page
+    )
+}
+
+/// Statistics gathered while talking to the API.
+#[derive(Debug, Default, Serialize)]
+struct FetchStats {
+    pages: u32,
+    /// Latency of each page request, in seconds
+    page_secs: Vec<f64>,
+    ratelimit_remaining: Option<u32>,
+    /// Oldest event we could get when the feed ran out before the window start
+    feed_truncated_at: Option<chrono::DateTime<Utc>>,
+}
+
+/// Decode events one at a time, skipping (with a warning) any that don't
+/// match what we expect instead of failing the whole batch.
+fn decode_events(values: Vec<serde_json::Value>) -> Vec<Event> {
+    values
+        .into_iter()
+        .filter_map(|v| match serde_json::from_value(v) {
+            Ok(e) => Some(e),
+            Err(e) => {
+                log::warn!("Skipping undecodable event: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Read events previously saved from the API, e.g. for --from-file.
+pub fn read_events_file(path: &str) -> Result<Vec<Event>> {
+    let f = std::io::BufReader::new(std::fs::File::open(path)?);
+    Ok(decode_events(serde_json::from_reader(f)?))
+}
+
+async fn query(
+    client: &github_v3::Client,
+    user: &str,
+    page: u32,
+    stats: &mut FetchStats,
+) -> Result<Vec<Event>> {
+    let t = std::time::Instant::now();
+    let resp = client
+        .get()
+        .path("users")
+        .arg(user)
+        .path("events/public")
+        .query(&format!("page={}", page))
+        .send()
+        .await?;
+    stats.pages += 1;
+    stats.page_secs.push(t.elapsed().as_secs_f64());
+    stats.ratelimit_remaining = resp
+        .headers()
+        .get("x-ratelimit-remaining")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok());
+    Ok(decode_events(resp.obj().await?))
+}
+
+async fn my_events(
+    client: &github_v3::Client,
+    user: &str,
+    start: &chrono::DateTime<Local>,
+    stats: &mut FetchStats,
+) -> Result<Vec<Box<Event>>> {
+    // Pages are numbered from 1; page 0 is served as page 1 again.
+    let mut page = 1u32;
+    let mut r = Vec::new();
+    let mut oldest: Option<chrono::DateTime<Utc>> = None;
+    loop {
+        log::info!("Querying page {}", page);
+        let mut events: Vec<Event> = query(client, user, page, stats).await?;
+        let exhausted = events.is_empty();
+        let mut found = false;
+        for e in events.drain(..) {
+            if e.actor.login != user {
+                continue;
+            }
+            let t = &e.created_at;
+            oldest = Some(oldest.map_or(*t, |o| o.min(*t)));
+            let in_timestamp = t > start;
+            if !in_timestamp {
+                continue;
+            }
+            found = true;
+            r.push(Box::new(e));
+        }
+        // GitHub only serves the most recent 300 events; if we run out (or
+        // hit our own page limit) while everything is still inside the
+        // window, the beginning of the window is missing.
+        if exhausted || (found && page > PAGE_LIMIT) {
+            if let Some(oldest) = oldest.filter(|t| t > start) {
+                log::warn!(
+                    "Event feed for {} ends at {}, after the window start {}",
+                    user,
+                    oldest,
+                    start
+                );
+                stats.feed_truncated_at = stats.feed_truncated_at.max(Some(oldest));
+            }
+            return Ok(r);
+        }
+        if !found {
+            return Ok(r);
+        }
+        page += 1;
+    }
+}
+
+/// Fetch recent events for a repository, newest first, until `start`.
+async fn repo_events(
+    client: &github_v3::Client,
+    repo: &str,
+    start: &chrono::DateTime<Local>,
+    stats: &mut FetchStats,
+) -> Result<Vec<Event>> {
+    let (owner, name) = repo
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("Invalid repository: {}", repo))?;
+    let mut r = Vec::new();
+    for page in 1..=PAGE_LIMIT {
+        let t = std::time::Instant::now();
+        let events = client
+            .get()
+            .path("repos")
+            .arg(owner)
+            .arg(name)
+            .path("events")
+            .query(&format!("page={}", page))
+            .send()
+            .await?
+            .obj()
+            .await?;
+        let events = decode_events(events);
+        stats.pages += 1;
+        stats.page_secs.push(t.elapsed().as_secs_f64());
+        let done = events.iter().all(|e| e.created_at <= *start);
+        r.extend(events.into_iter().filter(|e| e.created_at > *start));
+        if done {
+            break;
+        }
+    }
+    Ok(r)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReviewReaction {
+    Approved,
+    Other,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IssueActivity {
+    /// Whether I left the issue open (`true`, i.e. opened or reopened it)
+    /// or closed it
+    state: Option<bool>,
+    /// Number of my comments in the window
+    comments: u32,
+    /// Closed by someone after my last comment, within the window
+    #[serde(default)]
+    resolved: bool,
+}
+
+impl IssueActivity {
+    fn describe(&self) -> &'static str {
+        match self.state {
+            Some(true) => "opened",
+            Some(false) => "closed",
+            None => "commented",
+        }
+    }
+
+    /// ` ×N` for issues with several of my comments, so busy threads stand
+    /// out, and ` (since resolved)` for those closed after I commented.
+    fn suffix(&self) -> String {
+        let mut r = String::new();
+        if self.comments > 1 {
+            r.push_str(&format!(" ×{}", self.comments));
+        }
+        if self.resolved {
+            r.push_str(" (since resolved)");
+        }
+        r
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PullRequestAction {
+    Opened,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RepoEvents {
+    pr_action: BTreeMap<String, PullRequestAction>,
+    /// Pull requests I merged
+    #[serde(default, skip_serializing_if = "std::collections::BTreeSet::is_empty")]
+    merged: std::collections::BTreeSet<String>,
+    reviewed: BTreeMap<String, ReviewReaction>,
+    /// First line of my most recent review body on each pull request
+    #[serde(default)]
+    review_bodies: BTreeMap<String, String>,
+    /// First line of my most recent comment on each issue
+    #[serde(default)]
+    comment_bodies: BTreeMap<String, String>,
+    pushed: u32,
+    issues: BTreeMap<String, IssueActivity>,
+    titles: BTreeMap<String, String>,
+    /// Most recent activity on each URL
+    times: BTreeMap<String, chrono::DateTime<Utc>>,
+    last_push: Option<chrono::DateTime<Utc>>,
+    /// No issues or pull requests authored here before this window
+    first_contribution: bool,
+    /// Number of my commits pushed by others, keyed by pusher
+    pushed_by_others: BTreeMap<String, u32>,
+    /// Why enrichment couldn't be done, e.g. `repo archived`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unavailable: Option<String>,
+    /// Labels on each pull request and issue
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    labels: BTreeMap<String, Vec<String>>,
+}
+
+/// RepoEvents::unavailable for archived repositories.
+const ARCHIVED: &str = "repo archived";
+
+impl RepoEvents {
+    fn record_time(&mut self, url: &str, t: chrono::DateTime<Utc>) {
+        let v = self.times.entry(url.to_string()).or_insert(t);
+        *v = (*v).max(t);
+    }
+
+    /// ` (repo archived)` and the like, for repository headings.
+    fn unavailable_note(&self) -> String {
+        self.unavailable
+            .as_ref()
+            .map(|u| format!(" ({})", u))
+            .unwrap_or_default()
+    }
+
+    /// Whether enrichment couldn't reach this repository. Archived ones
+    /// are still readable, so their data is complete.
+    fn unreachable(&self) -> bool {
+        self.unavailable.as_deref().is_some_and(|u| u != ARCHIVED)
+    }
+
+    /// Fold in activity recorded for the same repository under another name.
+    fn merge(&mut self, other: RepoEvents) {
+        for (url, action) in other.pr_action {
+            self.pr_action.entry(url).or_insert(action);
+        }
+        self.merged.extend(other.merged);
+        for (url, reaction) in other.reviewed {
+            self.reviewed.entry(url).or_insert(reaction);
+        }
+        for (url, body) in other.review_bodies {
+            self.review_bodies.entry(url).or_insert(body);
+        }
+        for (url, body) in other.comment_bodies {
+            self.comment_bodies.entry(url).or_insert(body);
+        }
+        self.pushed += other.pushed;
+        for (url, activity) in other.issues {
+            let v = self.issues.entry(url).or_default();
+            v.state = v.state.or(activity.state);
+            v.comments += activity.comments;
+            v.resolved |= activity.resolved;
+        }
+        for (url, labels) in other.labels {
+            self.labels.entry(url).or_insert(labels);
+        }
+        for (url, title) in other.titles {
+            self.titles.entry(url).or_insert(title);
+        }
+        for (url, t) in other.times {
+            self.record_time(&url, t);
+        }
+        self.last_push = self.last_push.max(other.last_push);
+        self.first_contribution |= other.first_contribution;
+        self.unavailable = self.unavailable.take().or(other.unavailable);
+        for (pusher, n) in other.pushed_by_others {
+            *self.pushed_by_others.entry(pusher).or_default() += n;
+        }
+    }
+
+    /// Number of distinct things done in this repository; a batch of pushes
+    /// counts as one.
+    fn activity(&self) -> usize {
+        self.pr_action.len()
+            + self.reviewed.len()
+            + self.issues.len()
+            + if self.pushed > 0 { 1 } else { 0 }
+            + self.pushed_by_others.len()
+    }
+}
+
+type ParsedRepoEvents = BTreeMap<String, RepoEvents>;
+
+/// Activity in a window, grouped by repository.
+#[derive(Serialize, Deserialize)]
+pub struct RepoEventParseData {
+    repos: ParsedRepoEvents,
+    before: u32,
+    after: u32,
+    /// Number of events in each (local) day of the window
+    by_day: BTreeMap<NaiveDate, u32>,
+    /// Set if the event feed didn't reach back to the window start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    truncated_at: Option<chrono::DateTime<Utc>>,
+}
+
+/// Aggregate counts across all repositories.
+#[derive(Debug, Default)]
+struct Totals {
+    repos: usize,
+    pull_requests: usize,
+    reviews: usize,
+    issues: usize,
+    /// Comments on issues and pull requests
+    comments: u32,
+    pushes: u32,
+}
+
+/// One row of the report, used by the tabular exports.
+#[derive(Debug, Serialize)]
+struct ReportItem<'a> {
+    repo: &'a str,
+    kind: &'static str,
+    url: Option<&'a str>,
+    title: String,
+    state: String,
+    time: Option<chrono::DateTime<Utc>>,
+    count: u32,
+}
+
+impl RepoEventParseData {
+    /// Flatten the report into one item per pull request, review, issue
+    /// or batch of pushes.
+    fn items(&self) -> Vec<ReportItem<'_>> {
+        fn item<'a>(
+            repo: &'a str,
+            events: &'a RepoEvents,
+            kind: &'static str,
+            url: &'a str,
+            state: &str,
+        ) -> ReportItem<'a> {
+            ReportItem {
+                repo,
+                kind,
+                url: Some(url),
+                title: events.titles.get(url).cloned().unwrap_or_default(),
+                state: state.to_string(),
+                time: events.times.get(url).copied(),
+                count: 1,
+            }
+        }
+        let mut r = Vec::new();
+        for (repo, events) in self.repos.iter() {
+            let item = |kind, url, state| item(repo, events, kind, url, state);
+            for (url, action) in events.pr_action.iter() {
+                let state = match action {
+                    PullRequestAction::Opened => "opened",
+                };
+                r.push(item("pull_request", url, state));
+            }
+            for (url, reaction) in events.reviewed.iter() {
+                let state = match reaction {
+                    ReviewReaction::Approved => "approved",
+                    ReviewReaction::Other => "reviewed",
+                };
+                r.push(item("review", url, state));
+            }
+            for (url, activity) in events.issues.iter() {
+                r.push(ReportItem {
+                    count: activity.comments,
+                    ..item("issue", url, activity.describe())
+                });
+            }
+            if events.pushed > 0 {
+                r.push(ReportItem {
+                    repo: repo.as_str(),
+                    kind: "push",
+                    url: None,
+                    title: String::new(),
+                    state: "pushed".to_string(),
+                    time: events.last_push,
+                    count: events.pushed,
+                });
+            }
+            for (pusher, n) in events.pushed_by_others.iter() {
+                r.push(ReportItem {
+                    repo: repo.as_str(),
+                    kind: "push",
+                    url: None,
+                    title: String::new(),
+                    state: format!("authored, pushed by {}", pusher),
+                    time: None,
+                    count: *n,
+                });
+            }
+        }
+        r
+    }
+
+    fn totals(&self) -> Totals {
+        let mut t = Totals {
+            repos: self.repos.len(),
+            ..Default::default()
+        };
+        for events in self.repos.values() {
+            t.pull_requests += events.pr_action.len();
+            t.reviews += events.reviewed.len();
+            t.issues += events.issues.len();
+            t.comments += events.issues.values().map(|i| i.comments).sum::<u32>();
+            t.pushes += events.pushed;
+        }
+        t
+    }
+}
+
+/// Whether an event passes the --label-filter/--exclude-label options.
+/// Events not about a pull request or issue (e.g. pushes) have no labels,
+/// so they are dropped whenever --label-filter is used.
+fn label_filter_matches(e: &Event, include: &[String], exclude: &[String]) -> bool {
+    let labels = e.labels().unwrap_or_default();
+    let has = |names: &[String]| labels.iter().any(|l| names.contains(&l.name));
+    (include.is_empty() || has(include)) && !has(exclude)
+}
+
+/// The first non-blank line of a review or comment body.
+fn first_line(body: Option<&str>) -> Option<&str> {
+    body?.lines().map(str::trim).find(|l| !l.is_empty())
+}
+
+/// Issue numbers a pull request body says it closes, e.g. `Fixes #12`.
+fn closing_refs(body: &str) -> Vec<u64> {
+    const KEYWORDS: &[&str] = &[
+        "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+    ];
+    let words: Vec<&str> = body.split_whitespace().collect();
+    words
+        .windows(2)
+        .filter(|w| {
+            let k = w[0].trim_end_matches(':').to_ascii_lowercase();
+            KEYWORDS.contains(&k.as_str())
+        })
+        .filter_map(|w| {
+            w[1].strip_prefix('#')?
+                .trim_end_matches(|c: char| !c.is_ascii_digit())
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+fn skip_malformed(e: &Event) {
+    log::warn!("Skipping {} {} with missing payload fields", e.typ, e.id);
+}
+
+/// Group the events between `start` and `end` by repository, counting
+/// those outside the window.
+pub fn parse_events(
+    events: impl IntoIterator<Item = Box<Event>>,
+    start: &chrono::DateTime<Local>,
+    end: &chrono::DateTime<Local>,
+) -> RepoEventParseData {
+    let mut r: ParsedRepoEvents = Default::default();
+    let mut before = 0;
+    let mut after = 0;
+    let mut by_day: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    for e in events {
+        let t = &e.created_at;
+        if t > end {
+            after += 1;
+            continue;
+        }
+        if t < start {
+            before += 1;
+            continue;
+        }
+        let repoevents = r.entry(e.repo.name.clone()).or_default();
+        match e.typ.as_str() {
+            "PushEvent" => {
+                repoevents.pushed += 1;
+                repoevents.last_push = repoevents.last_push.max(Some(e.created_at));
+            }
+            "PullRequestEvent" => {
+                let (pr, action) = match (&e.payload.pull_request, &e.payload.action) {
+                    (Some(pr), Some(action)) => (pr, action.as_str()),
+                    _ => {
+                        skip_malformed(&e);
+                        continue;
+                    }
+                };
+                let url = pr.html_url.as_str();
+                let v = match action {
+                    "opened" => PullRequestAction::Opened,
+                    "closed" if pr.merged == Some(true) => {
+                        repoevents.merged.insert(url.to_string());
+                        // Issues this merge closed count as closed by me
+                        for n in closing_refs(pr.body.as_deref().unwrap_or_default()) {
+                            let url = format!("https://github.com/{}/issues/{}", e.repo.name, n);
+                            repoevents
+                                .issues
+                                .entry(url.clone())
+                                .or_default()
+                                .state
+                                .get_or_insert(false);
+                            repoevents
+                                .titles
+                                .entry(url.clone())
+                                .or_insert_with(|| format!("#{}", n));
+                            repoevents.record_time(&url, e.created_at);
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                repoevents.pr_action.entry(url.to_string()).or_insert(v);
+                repoevents
+                    .titles
+                    .entry(url.to_string())
+                    .or_insert_with(|| pr.title.clone());
+                repoevents.record_time(url, e.created_at);
+            }
+            "PullRequestReviewEvent" => {
+                let (review, pr) = match (&e.payload.review, &e.payload.pull_request) {
+                    (Some(review), Some(pr)) => (review, pr),
+                    _ => {
+                        skip_malformed(&e);
+                        continue;
+                    }
+                };
+                let url = pr.html_url.as_str();
+                repoevents
+                    .reviewed
+                    .entry(url.to_string())
+                    .or_insert_with(|| match review.state.as_str() {
+                        "approved" => ReviewReaction::Approved,
+                        _ => ReviewReaction::Other,
+                    });
+                if let Some(excerpt) = first_line(review.body.as_deref()) {
+                    repoevents
+                        .review_bodies
+                        .entry(url.to_string())
+                        .or_insert_with(|| excerpt.to_string());
+                }
+                repoevents
+                    .titles
+                    .entry(url.to_string())
+                    .or_insert_with(|| pr.title.clone());
+                repoevents.record_time(url, e.created_at);
+            }
+            "IssueCommentEvent" => {
+                let issue = match e.payload.issue {
+                    Some(ref issue) => issue,
+                    None => {
+                        skip_malformed(&e);
+                        continue;
+                    }
+                };
+                let url = issue.html_url.as_str();
+                repoevents
+                    .issues
+                    .entry(url.to_string())
+                    .or_default()
+                    .comments += 1;
+                let comment = e.payload.comment.as_ref();
+                if let Some(excerpt) = first_line(comment.and_then(|c| c.body.as_deref())) {
+                    repoevents
+                        .comment_bodies
+                        .entry(url.to_string())
+                        .or_insert_with(|| excerpt.to_string());
+                }
+                repoevents
+                    .titles
+                    .entry(url.to_string())
+                    .or_insert_with(|| issue.title.clone());
+                repoevents.record_time(url, e.created_at);
+            }
+            "IssuesEvent" => {
+                let (issue, action) = match (&e.payload.issue, &e.payload.action) {
+                    (Some(issue), Some(action)) => (issue, action.as_str()),
+                    _ => {
+                        skip_malformed(&e);
+                        continue;
+                    }
+                };
+                let state = match action {
+                    "opened" | "reopened" => true,
+                    "closed" => false,
+                    _ => continue,
+                };
+                let url = issue.html_url.as_str();
+                // Events are newest first, so this keeps the final state
+                repoevents
+                    .issues
+                    .entry(url.to_string())
+                    .or_default()
+                    .state
+                    .get_or_insert(state);
+                repoevents
+                    .titles
+                    .entry(url.to_string())
+                    .or_insert_with(|| issue.title.clone());
+                repoevents.record_time(url, e.created_at);
+            }
+            _ => continue,
+        };
+        if let (Some(url), Some(labels)) = (e.item_url(), e.labels()) {
+            if !labels.is_empty() {
+                repoevents
+                    .labels
+                    .entry(url.to_string())
+                    .or_insert_with(|| labels.iter().map(|l| l.name.clone()).collect());
+            }
+        }
+        *by_day
+            .entry(e.created_at.with_timezone(&Local).date_naive())
+            .or_default() += 1;
+    }
+    for (_, events) in r.iter_mut() {
+        for (url, _) in events.pr_action.iter() {
+            // Don't double-count discussion on new PRs
+            events.issues.remove(url);
+            events.reviewed.remove(url);
+        }
+        for (url, _) in events.reviewed.iter() {
+            // Don't double-count discussion on reviewed PRs
+            events.issues.remove(url);
+        }
+    }
+    RepoEventParseData {
+        repos: r,
+        before,
+        after,
+        by_day,
+        truncated_at: None,
+    }
+}
+
+/// Credit commits authored with one of `emails` but pushed by someone other
+/// than `logins` to the repository they were pushed to.
+fn attribute_pushes(
+    data: &mut RepoEventParseData,
+    events: &[Event],
+    logins: &[&str],
+    emails: &[String],
+    start: &chrono::DateTime<Local>,
+    end: &chrono::DateTime<Local>,
+) {
+    for e in events {
+        if e.typ != "PushEvent"
+            || e.created_at < *start
+            || e.created_at > *end
+            || logins
+                .iter()
+                .any(|l| l.eq_ignore_ascii_case(&e.actor.login))
+        {
+            continue;
+        }
+        let mine = e
+            .payload
+            .commits
+            .iter()
+            .flatten()
+            .filter(|c| {
+                emails
+                    .iter()
+                    .any(|m| m.eq_ignore_ascii_case(&c.author.email))
+            })
+            .count() as u32;
+        if mine > 0 {
+            let repoevents = data.repos.entry(e.repo.name.clone()).or_default();
+            *repoevents
+                .pushed_by_others
+                .entry(e.actor.login.clone())
+                .or_default() += mine;
+        }
+    }
+}
+
+/// Whether `e` is GitHub reporting that something no longer exists (or
+/// isn't visible to us), e.g. a deleted repository.
+fn is_gone(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<github_v3::GHError>(),
+        Some(github_v3::GHError::Response { status, .. }) if matches!(status.as_u16(), 404 | 410)
+    )
+}
+
+fn is_unprocessable(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<github_v3::GHError>(),
+        Some(github_v3::GHError::Response { status, .. }) if status.as_u16() == 422
+    )
+}
+
+/// Treat `what` being gone as having no data instead of failing the report.
+fn unless_gone<T: Default>(r: Result<T>, what: &str) -> Result<T> {
+    match r {
+        Err(e) if is_gone(&e) => {
+            log::warn!("Skipping {}: {}", what, e);
+            Ok(T::default())
+        }
+        r => r,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoName {
+    full_name: String,
+    #[serde(default)]
+    archived: bool,
+}
+
+/// The current name of `repo`; the API follows renames and transfers.
+async fn current_repo_name(client: &github_v3::Client, repo: &str) -> Result<RepoName> {
+    let (owner, name) = repo
+        .split_once('/')
+        .ok_or_else(|| anyhow::anyhow!("Invalid repository: {}", repo))?;
+    let r: RepoName = client
+        .get()
+        .path("repos")
+        .arg(owner)
+        .arg(name)
+        .send()
+        .await?
+        .obj()
+        .await?;
+    Ok(r)
+}
+
+/// Merge activity recorded under old names of renamed or transferred
+/// repositories into their current name. `names` caches lookups across
+/// windows.
+async fn resolve_renames(
+    client: &github_v3::Client,
+    events: &mut RepoEventParseData,
+    names: &mut HashMap<String, (String, Option<&'static str>)>,
+) -> Result<()> {
+    let repos: Vec<String> = events.repos.keys().cloned().collect();
+    for repo in repos {
+        let (current, unavailable) = match names.get(&repo) {
+            Some(n) => n.clone(),
+            None => {
+                let n = match current_repo_name(client, &repo).await {
+                    Ok(r) if r.archived => (r.full_name, Some(ARCHIVED)),
+                    Ok(r) => (r.full_name, None),
+                    // Deleted since; keep the name we have
+                    Err(e) if is_gone(&e) => (repo.clone(), Some("repo deleted")),
+                    Err(e) => {
+                        log::warn!("Failed to look up {}: {}", repo, e);
+                        (repo.clone(), None)
+                    }
+                };
+                names.insert(repo.clone(), n.clone());
+                n
+            }
+        };
+        if let Some(u) = unavailable {
+            if let Some(r) = events.repos.get_mut(&repo) {
+                r.unavailable = Some(u.to_string());
+            }
+        }
+        if current != repo {
+            if let Some(old) = events.repos.remove(&repo) {
+                events.repos.entry(current).or_default().merge(old);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchCount {
+    total_count: u64,
+}
+
+/// Mark repositories where `user` had not authored any issue or pull
+/// request before `start`.
+async fn detect_first_contributions(
+    client: &github_v3::Client,
+    user: &str,
+    start: &chrono::DateTime<Local>,
+    events: &mut RepoEventParseData,
+) -> Result<()> {
+    let before = start.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%SZ");
+    for (repo, repoevents) in events.repos.iter_mut() {
+        let owner = repo.split('/').next().unwrap_or_default();
+        if owner.eq_ignore_ascii_case(user) || repoevents.activity() == 0 {
+            continue;
+        }
+        let q = format!("repo:{} author:{} created:<{}", repo, user, before);
+        let r: Result<SearchCount> = async {
+            Ok(client
+                .get()
+                .path("search/issues")
+                .query("q=")
+                .arg(&q)
+                .send()
+                .await?
+                .obj()
+                .await?)
+        }
+        .await;
+        match r {
+            Ok(r) => repoevents.first_contribution = r.total_count == 0,
+            // Searching a repository that no longer exists fails validation
+            Err(e) if is_gone(&e) || is_unprocessable(&e) => {
+                log::warn!("Skipping first contribution check for {}: {}", repo, e);
+                repoevents.unavailable = Some("repo unavailable".to_string());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct TimelineEvent {
+    event: String,
+    created_at: Option<chrono::DateTime<Utc>>,
+}
+
+/// Whether the issue at `url` was closed in `(after, until]`.
+async fn closed_between(
+    client: &github_v3::Client,
+    url: &str,
+    after: &chrono::DateTime<Utc>,
+    until: &chrono::DateTime<Utc>,
+) -> Result<bool> {
+    const PER_PAGE: usize = 100;
+    // https://github.com/{owner}/{repo}/issues/{n}, or .../pull/{n}
+    let parts: Vec<&str> = url.rsplitn(4, '/').collect();
+    let (n, repo, owner) = match parts.as_slice() {
+        [n, _, repo, rest] => (*n, *repo, rest.rsplit('/').next().unwrap_or_default()),
+        _ => anyhow::bail!("Unexpected issue URL: {}", url),
+    };
+    let mut page = 1;
+    loop {
+        let events: Vec<TimelineEvent> = client
+            .get()
+            .path("repos")
+            .arg(owner)
+            .arg(repo)
+            .path("issues")
+            .arg(n)
+            .path("timeline")
+            .query(&format!("per_page={}&page={}", PER_PAGE, page))
+            .send()
+            .await?
+            .obj()
+            .await?;
+        let closed = events.iter().any(|e| {
+            e.event == "closed" && e.created_at.is_some_and(|t| &t > after && &t <= until)
+        });
+        if closed {
+            return Ok(true);
+        }
+        if events.len() < PER_PAGE {
+            return Ok(false);
+        }
+        page += 1;
+    }
+}
+
+/// Mark issues I only commented on as resolved if they were closed after my
+/// last comment and before the end of the window.
+async fn check_resolved(
+    client: &github_v3::Client,
+    end: &chrono::DateTime<Local>,
+    events: &mut RepoEventParseData,
+) -> Result<()> {
+    let until = end.with_timezone(&Utc);
+    for (repo, repoevents) in events.repos.iter_mut() {
+        for (url, activity) in repoevents.issues.iter_mut() {
+            let after = match repoevents.times.get(url) {
+                Some(t) if activity.state.is_none() => t,
+                _ => continue,
+            };
+            match closed_between(client, url, after, &until).await {
+                Ok(r) => activity.resolved = r,
+                Err(e) if is_gone(&e) => {
+                    log::warn!("Skipping resolution check for {}: {}", repo, e);
+                    repoevents.unavailable = Some("repo unavailable".to_string());
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchItem {
+    html_url: String,
+    title: String,
+    repository_url: String,
+    pull_request: Option<serde_json::Value>,
+    updated_at: chrono::DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResults {
+    items: Vec<SearchItem>,
+}
+
+/// Fill in pull requests opened and issues commented on between `start`
+/// and `until` from the search API, for when the event feed doesn't reach
+/// back that far.
+async fn search_backfill(
+    client: &github_v3::Client,
+    user: &str,
+    start: &chrono::DateTime<Local>,
+    until: &chrono::DateTime<Utc>,
+    events: &mut RepoEventParseData,
+) -> Result<()> {
+    let fmt = |t: chrono::DateTime<Utc>| t.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let range = format!("{}..{}", fmt(start.with_timezone(&Utc)), fmt(*until));
+    for (q, opened) in [
+        (format!("author:{} is:pr created:{}", user, range), true),
+        (format!("commenter:{} updated:{}", user, range), false),
+    ] {
+        let r: SearchResults = client
+            .get()
+            .path("search/issues")
+            .query("q=")
+            .arg(&q)
+            .send()
+            .await?
+            .obj()
+            .await?;
+        for item in r.items {
+            let repo = item
+                .repository_url
+                .trim_start_matches("https://api.github.com/repos/")
+                .to_string();
+            let repoevents = events.repos.entry(repo).or_default();
+            let url = item.html_url;
+            if opened && item.pull_request.is_some() {
+                repoevents
+                    .pr_action
+                    .insert(url.clone(), PullRequestAction::Opened);
+            } else if !repoevents.pr_action.contains_key(&url) {
+                repoevents
+                    .issues
+                    .entry(url.clone())
+                    .or_insert(IssueActivity {
+                        comments: 1,
+                        ..Default::default()
+                    });
+            } else {
+                continue;
+            }
+            repoevents.record_time(&url, item.updated_at);
+            repoevents.titles.entry(url).or_insert(item.title);
+        }
+    }
+    Ok(())
+}
+
+fn link<L: AsRef<str>, T: AsRef<str>>(link: L, title: T) -> String {
+    format!("[{}]({})", title.as_ref().trim(), link.as_ref().trim())
+}
+
+type IssueSection<'a> = (
+    &'static str,
+    &'static str,
+    &'static str,
+    Vec<(&'a String, &'a IssueActivity)>,
+);
+
+/// Issues grouped by what I did with them, as (heading, emoji, what the
+/// emoji stands for, issues); empty groups are left out.
+fn issue_sections(events: &RepoEvents) -> Vec<IssueSection<'_>> {
+    let mut r: Vec<IssueSection> = vec![
+        ("Opened issues", "🆕", "opened", Vec::new()),
+        ("Closed", "✔", "closed", Vec::new()),
+        ("Commented", "📝", "commented", Vec::new()),
+    ];
+    for (url, activity) in events.issues.iter() {
+        let i = match activity.state {
+            Some(true) => 0,
+            Some(false) => 1,
+            None => 2,
+        };
+        r[i].3.push((url, activity));
+    }
+    r.retain(|(_, _, _, v)| !v.is_empty());
+    r
+}
+
+/// Options affecting how the report is rendered.
+#[derive(Debug, Default)]
+pub struct RenderOptions {
+    /// Wrap repositories in `<details>`, except for this many of the most active.
+    pub collapse_after: Option<usize>,
+    /// If set, group repositories by whether these owners/repos match.
+    pub mine: Option<Vec<String>>,
+    /// Leave out the emoji prefixes in plain text output.
+    pub no_emoji: bool,
+    /// Replace emoji with text badges in HTML output.
+    pub no_emoji_html: bool,
+    /// Quote the start of each review under the reviewed pull request.
+    pub review_bodies: bool,
+    /// Quote the start of each comment under the issue.
+    pub comment_bodies: bool,
+    /// Format times and counts for this locale.
+    pub locale: Option<chrono::Locale>,
+    /// One summary line per repository instead of the full report.
+    pub digest: bool,
+    /// Wrap each category in `<details>`.
+    pub collapse_categories: bool,
+    /// Entries to show per section, with a count of the rest.
+    pub max_items: Option<usize>,
+}
+
+impl RenderOptions {
+    fn from_opt(opt: &Opt) -> Self {
+        Self {
+            collapse_after: if opt.collapsible || opt.collapsible_top.is_some() {
+                Some(opt.collapsible_top.unwrap_or(0))
+            } else {
+                None
+            },
+            mine: if opt.split_ownership {
+                let mut v = vec![opt.user.clone()];
+                v.extend(opt.also_login.iter().cloned());
+                v.extend(opt.maintain.iter().cloned());
+                Some(v)
+            } else {
+                None
+            },
+            no_emoji: opt.no_emoji,
+            no_emoji_html: opt.no_emoji_html,
+            review_bodies: opt.show_review_bodies,
+            comment_bodies: opt.show_comment_excerpts,
+            locale: opt.locale,
+            digest: opt.digest,
+            collapse_categories: opt.collapsible_categories,
+            max_items: opt.max_items,
+        }
+    }
+
+    /// Entries to show per section.
+    fn max_items(&self) -> usize {
+        self.max_items.unwrap_or(usize::MAX)
+    }
+
+    fn is_mine(mine: &[String], repo: &str) -> bool {
+        let owner = repo.split('/').next().unwrap_or(repo);
+        mine.iter()
+            .any(|m| m.eq_ignore_ascii_case(repo) || m.eq_ignore_ascii_case(owner))
+    }
+}
+
+type RepoGroup<'a> = (Option<&'static str>, Vec<(&'a String, &'a RepoEvents)>);
+
+/// Repositories to render, grouped under optional headings.
+fn repo_groups<'a>(events: &'a RepoEventParseData, opts: &RenderOptions) -> Vec<RepoGroup<'a>> {
+    match opts.mine {
+        Some(ref mine) => {
+            let (m, o): (Vec<_>, Vec<_>) = events
+                .repos
+                .iter()
+                .partition(|(r, _)| RenderOptions::is_mine(mine, r));
+            vec![
+                (Some("My projects"), m),
+                (Some("Contributions elsewhere"), o),
+            ]
+        }
+        None => vec![(None, events.repos.iter().collect())],
+    }
+}
+
+/// The repositories that should be rendered collapsed.
+fn collapsed_repos<'a>(
+    events: &'a RepoEventParseData,
+    opts: &RenderOptions,
+) -> std::collections::HashSet<&'a str> {
+    let keep = match opts.collapse_after {
+        Some(n) => n,
+        None => return Default::default(),
+    };
+    let mut repos: Vec<_> = events.repos.iter().collect();
+    repos.sort_by_key(|(_, e)| std::cmp::Reverse(e.activity()));
+    repos
+        .into_iter()
+        .skip(keep)
+        .map(|(r, _)| r.as_str())
+        .collect()
+}
+
+/// Escape characters Markdown would otherwise interpret.
+fn markdown_escape(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|' | '!'
+        ) {
+            r.push('\\');
+        }
+        r.push(c);
+    }
+    r
+}
+
+/// `…and K more`, when --max-items hides some of a section's `n` entries.
+fn more_items(n: usize, opts: &RenderOptions) -> Option<String> {
+    let hidden = n.saturating_sub(opts.max_items());
+    (hidden > 0).then(|| format!("…and {} more", format_count(hidden as u32, opts.locale)))
+}
+
+/// The (truncated) start of my review or comment on `url`, if requested.
+fn excerpt(bodies: &BTreeMap<String, String>, url: &str, enabled: bool) -> Option<String> {
+    const MAX: usize = 100;
+    if !enabled {
+        return None;
+    }
+    let body = bodies.get(url)?;
+    Some(if body.chars().count() > MAX {
+        format!("{}…", body.chars().take(MAX).collect::<String>().trim_end())
+    } else {
+        body.clone()
+    })
+}
+
+/// One entry of a [`Category`].
+struct CategoryItem<'a> {
+    url: &'a str,
+    title: &'a str,
+    /// Emoji for what I did, e.g. `✔` for an approving review
+    icon: &'static str,
+    /// What `icon` stands for, e.g. "approved"
+    meaning: &'static str,
+    /// What I did, e.g. "opened"
+    state: &'static str,
+    /// Comment count and resolution, e.g. ` ×3`
+    suffix: String,
+    /// The start of my review or comment, if requested
+    excerpt: Option<String>,
+}
+
+/// A list of a repository's pull requests, reviews or issues, as all the
+/// renderers show it: at most --max-items entries and a count of the rest.
+struct Category<'a> {
+    heading: &'static str,
+    /// What each entry is, e.g. "Pull Request"
+    kind: &'static str,
+    /// Number of entries, including those left out
+    len: usize,
+    items: Vec<CategoryItem<'a>>,
+    /// `…and K more`, if some entries were left out
+    more: Option<String>,
+}
+
+/// The non-empty categories of a repository's activity, in report order.
+fn categories<'a>(events: &'a RepoEvents, opts: &RenderOptions) -> Vec<Category<'a>> {
+    let title = |url: &str| events.titles.get(url).map(|s| s.as_str()).unwrap_or("");
+    let category = |heading, kind, items: Vec<CategoryItem<'a>>| {
+        let len = items.len();
+        Category {
+            heading,
+            kind,
+            len,
+            more: more_items(len, opts),
+            items: items.into_iter().take(opts.max_items()).collect(),
+        }
+    };
+    let mut r = Vec::new();
+    if !events.pr_action.is_empty() {
+        let items = events
+            .pr_action
+            .iter()
+            .map(|(url, action)| {
+                let state = match action {
+                    PullRequestAction::Opened => "opened",
+                };
+                CategoryItem {
+                    url,
+                    title: title(url),
+                    icon: "🆕",
+                    meaning: "new",
+                    state,
+                    suffix: String::new(),
+                    excerpt: None,
+                }
+            })
+            .collect();
+        r.push(category("Pull Requests", "Pull Request", items));
+    }
+    if !events.reviewed.is_empty() {
+        let items = events
+            .reviewed
+            .iter()
+            .map(|(url, reaction)| {
+                let (icon, meaning) = match reaction {
+                    ReviewReaction::Approved => ("✔", "approved"),
+                    ReviewReaction::Other => ("📋", "reviewed"),
+                };
+                CategoryItem {
+                    url,
+                    title: title(url),
+                    icon,
+                    meaning,
+                    state: meaning,
+                    suffix: String::new(),
+                    excerpt: excerpt(&events.review_bodies, url, opts.review_bodies),
+                }
+            })
+            .collect();
+        r.push(category("Reviewed", "Review", items));
+    }
+    for (heading, icon, meaning, issues) in issue_sections(events) {
+        let items = issues
+            .into_iter()
+            .map(|(url, activity)| CategoryItem {
+                url,
+                title: title(url),
+                icon,
+                meaning,
+                state: activity.describe(),
+                suffix: activity.suffix(),
+                excerpt: excerpt(&events.comment_bodies, url, opts.comment_bodies),
+            })
+            .collect();
+        r.push(category(heading, "Issue", items));
+    }
+    r
+}
+
+fn print_events(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    writeln!(
+        w,
+        "<!-- before: {} after: {} -->",
+        events.before, events.after
+    )?;
+    let collapsed = collapsed_repos(events, opts);
+    for (heading, repos) in repo_groups(events, opts) {
+        if let Some(heading) = heading {
+            if repos.is_empty() {
+                continue;
+            }
+            writeln!(w, "## {}", heading)?;
+            writeln!(w)?;
+        }
+        for (repo, events) in repos {
+            print_repo(w, repo, events, collapsed.contains(repo.as_str()), opts)?;
+        }
+    }
+    Ok(())
+}
+
+/// A category label such as `Reviewed:`, or with --collapsible-categories
+/// the start of a `<details>` block.
+fn category_start(
+    w: &mut impl Write,
+    heading: &str,
+    n: usize,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    if opts.collapse_categories {
+        writeln!(w, "<details>")?;
+        writeln!(w, "<summary>{} ({})</summary>", heading, n)?;
+        writeln!(w)
+    } else {
+        writeln!(w, "{}: ", heading)
+    }
+}
+
+fn category_end(w: &mut impl Write, opts: &RenderOptions) -> std::io::Result<()> {
+    writeln!(w)?;
+    if opts.collapse_categories {
+        writeln!(w, "</details>")?;
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+fn print_repo(
+    w: &mut impl Write,
+    repo: &str,
+    events: &RepoEvents,
+    collapse: bool,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    let url = format!("https://github.com/{}", repo);
+    if collapse {
+        writeln!(w, "<details>")?;
+        writeln!(
+            w,
+            "<summary><a href=\"{}\">{}</a> ({})</summary>",
+            url,
+            repo,
+            events.activity()
+        )?;
+        writeln!(w)?;
+    } else {
+        let marker = if events.first_contribution {
+            " 🎉 first contribution"
+        } else {
+            ""
+        };
+        writeln!(
+            w,
+            "### {}{}{}",
+            link(url.as_str(), repo),
+            marker,
+            events.unavailable_note()
+        )?;
+    }
+    for c in categories(events, opts) {
+        category_start(w, c.heading, c.len, opts)?;
+        for i in c.items {
+            writeln!(w, "  - {} {}{}", i.icon, link(i.url, i.title), i.suffix)?;
+            if let Some(body) = i.excerpt {
+                writeln!(w, "    > {}", markdown_escape(&body))?;
+            }
+        }
+        if let Some(more) = c.more {
+            writeln!(w, "  - {}", more)?;
+        }
+        category_end(w, opts)?;
+    }
+    if events.pushed > 0 {
+        writeln!(
+            w,
+            "Pushed {} times",
+            format_count(events.pushed, opts.locale)
+        )?;
+        writeln!(w)?;
+    }
+    for (pusher, n) in events.pushed_by_others.iter() {
+        writeln!(
+            w,
+            "Authored {}, pushed by {}",
+            plural(*n as usize, "commit"),
+            pusher
+        )?;
+        writeln!(w)?;
+    }
+    if collapse {
+        writeln!(w, "</details>")?;
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// An AsciiDoc `link:` macro.
+fn asciidoc_link(url: &str, title: &str) -> String {
+    format!("link:{}[{}]", url.trim(), title.trim().replace(']', "\\]"))
+}
+
+/// Render the report as AsciiDoc, with a section per repository.
+fn print_events_asciidoc(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    writeln!(w, "// before: {} after: {}", events.before, events.after)?;
+    writeln!(w)?;
+    for (heading, repos) in repo_groups(events, opts) {
+        let level = if let Some(heading) = heading {
+            if repos.is_empty() {
+                continue;
+            }
+            writeln!(w, "== {}", heading)?;
+            writeln!(w)?;
+            "==="
+        } else {
+            "=="
+        };
+        for (repo, events) in repos {
+            let marker = if events.first_contribution {
+                " 🎉 first contribution"
+            } else {
+                ""
+            };
+            let url = format!("https://github.com/{}", repo);
+            writeln!(
+                w,
+                "{} {}{}{}",
+                level,
+                asciidoc_link(&url, repo),
+                marker,
+                events.unavailable_note()
+            )?;
+            writeln!(w)?;
+            for c in categories(events, opts) {
+                writeln!(w, ".{}", c.heading)?;
+                for i in c.items {
+                    let link = asciidoc_link(i.url, i.title);
+                    writeln!(w, "* {} {}{}", i.icon, link, i.suffix)?;
+                    // Attach a quote block to the list item.
+                    if let Some(body) = i.excerpt {
+                        writeln!(w, "+\n____\n{}\n____", body)?;
+                    }
+                }
+                if let Some(more) = c.more {
+                    writeln!(w, "* {}", more)?;
+                }
+                writeln!(w)?;
+            }
+            if events.pushed > 0 {
+                writeln!(
+                    w,
+                    "Pushed {} times",
+                    format_count(events.pushed, opts.locale)
+                )?;
+                writeln!(w)?;
+            }
+            for (pusher, n) in events.pushed_by_others.iter() {
+                writeln!(
+                    w,
+                    "Authored {}, pushed by {}",
+                    plural(*n as usize, "commit"),
+                    pusher
+                )?;
+                writeln!(w)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write an Atom feed with one entry per report item that has a timestamp.
+fn print_events_atom(
+    w: &mut impl Write,
+    title: &str,
+    events: &RepoEventParseData,
+) -> std::io::Result<()> {
+    let mut items: Vec<_> = events
+        .items()
+        .into_iter()
+        .filter(|i| i.time.is_some())
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.time));
+    let updated = items.first().and_then(|i| i.time).unwrap_or_else(Utc::now);
+    writeln!(w, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+    writeln!(w, r#"<feed xmlns="http://www.w3.org/2005/Atom">"#)?;
+    writeln!(w, "  <title>{}</title>", xml_escape(title))?;
+    writeln!(w, "  <id>urn:mygithubstatus:{}</id>", xml_escape(title))?;
+    writeln!(w, "  <updated>{}</updated>", updated.to_rfc3339())?;
+    for item in items {
+        let time = item.time.expect("filtered above");
+        let repo_url = format!("https://github.com/{}", item.repo);
+        let url = item.url.unwrap_or(&repo_url);
+        let summary = if item.title.is_empty() {
+            format!(
+                "{} in {} ({})",
+                item.state,
+                item.repo,
+                plural(item.count as usize, item.kind)
+            )
+        } else {
+            format!(
+                "{} {}: {}",
+                item.state,
+                item.kind.replace('_', " "),
+                item.title.trim()
+            )
+        };
+        writeln!(w, "  <entry>")?;
+        writeln!(w, "    <title>{}</title>", xml_escape(&summary))?;
+        writeln!(
+            w,
+            "    <id>{}#{}-{}</id>",
+            xml_escape(url),
+            item.kind,
+            time.timestamp()
+        )?;
+        writeln!(w, "    <link href=\"{}\"/>", xml_escape(url))?;
+        writeln!(w, "    <updated>{}</updated>", time.to_rfc3339())?;
+        writeln!(w, "    <category term=\"{}\"/>", xml_escape(item.repo))?;
+        writeln!(w, "  </entry>")?;
+    }
+    writeln!(w, "</feed>")?;
+    Ok(())
+}
+
+/// Quote a CSV field if needed (RFC 4180).
+fn csv_field(s: &str) -> std::borrow::Cow<'_, str> {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\"")).into()
+    } else {
+        s.into()
+    }
+}
+
+/// Write one CSV row per report item.
+fn print_events_csv(w: &mut impl Write, events: &RepoEventParseData) -> std::io::Result<()> {
+    writeln!(w, "repo,type,state,url,title,timestamp,count")?;
+    for item in events.items() {
+        writeln!(
+            w,
+            "{},{},{},{},{},{},{}",
+            csv_field(item.repo),
+            item.kind,
+            csv_field(&item.state),
+            csv_field(item.url.unwrap_or("")),
+            csv_field(item.title.trim()),
+            item.time.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            item.count
+        )?;
+    }
+    Ok(())
+}
+
+/// `org/repo: 2 PRs opened, 3 reviews, 5 issues commented, 4 pushes`
+fn print_events_digest(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+    markdown: bool,
+) -> std::io::Result<()> {
+    for (heading, repos) in repo_groups(events, opts) {
+        if let Some(heading) = heading {
+            if repos.is_empty() {
+                continue;
+            }
+            if markdown {
+                writeln!(w, "## {}", heading)?;
+            } else {
+                writeln!(w, "{}:", heading)?;
+            }
+        }
+        for (repo, events) in repos {
+            let mut parts = Vec::new();
+            if !events.pr_action.is_empty() {
+                parts.push(format!("{} opened", plural(events.pr_action.len(), "PR")));
+            }
+            if !events.reviewed.is_empty() {
+                parts.push(plural(events.reviewed.len(), "review"));
+            }
+            let mut issues: BTreeMap<&str, usize> = BTreeMap::new();
+            for activity in events.issues.values() {
+                *issues.entry(activity.describe()).or_default() += 1;
+            }
+            for state in ["opened", "closed", "commented"] {
+                if let Some(n) = issues.get(state) {
+                    parts.push(format!("{} {}", plural(*n, "issue"), state));
+                }
+            }
+            if events.pushed > 0 {
+                parts.push(plural(events.pushed as usize, "push"));
+            }
+            let pushed_by_others: u32 = events.pushed_by_others.values().sum();
+            if pushed_by_others > 0 {
+                let n = plural(pushed_by_others as usize, "commit");
+                parts.push(format!("{} pushed by others", n));
+            }
+            if parts.is_empty() {
+                continue;
+            }
+            if markdown {
+                let url = format!("https://github.com/{}", repo);
+                writeln!(w, "- {}: {}", link(&url, repo), parts.join(", "))?;
+            } else {
+                writeln!(w, "{}: {}", repo, parts.join(", "))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A Mermaid `timeline` with a section per day and an entry per time of
+/// day, fenced so it renders inline on GitHub.
+fn print_events_mermaid(w: &mut impl Write, events: &RepoEventParseData) -> std::io::Result<()> {
+    // `:` separates events and `#` starts an entity code.
+    let escape = |s: &str| s.trim().replace('#', "#35;").replace(':', "#58;");
+    let mut items: Vec<_> = events
+        .items()
+        .into_iter()
+        .filter(|i| i.time.is_some())
+        .collect();
+    items.sort_by_key(|i| i.time);
+    writeln!(w, "```mermaid")?;
+    writeln!(w, "timeline")?;
+    writeln!(w, "    title GitHub activity")?;
+    let mut day = None;
+    let mut period = None;
+    for item in items {
+        let t = item.time.expect("filtered above").with_timezone(&Local);
+        if day != Some(t.date_naive()) {
+            day = Some(t.date_naive());
+            period = None;
+            writeln!(w, "    section {}", t.format("%a %Y-%m-%d"))?;
+        }
+        let text = if item.title.is_empty() {
+            format!("{} in {}", item.state, item.repo)
+        } else {
+            let kind = item.kind.replace('_', " ");
+            format!("{} {} {}", item.state, kind, item.title)
+        };
+        let hm = escape(&t.format("%H:%M").to_string());
+        if period.as_ref() == Some(&hm) {
+            writeln!(w, "              : {}", escape(&text))?;
+        } else {
+            writeln!(w, "        {} : {}", hm, escape(&text))?;
+            period = Some(hm);
+        }
+    }
+    writeln!(w, "```")?;
+    Ok(())
+}
+
+/// Pull requests I opened, reviews that weren't approvals, and issues
+/// still open, as `URL repo: state kind: title` lines. In vim, load them
+/// with `:set errorformat=%f\ %m` and `:cexpr system('mygithubstatus ...')`.
+fn print_events_quickfix(w: &mut impl Write, events: &RepoEventParseData) -> std::io::Result<()> {
+    let mut items: Vec<_> = events
+        .items()
+        .into_iter()
+        .filter(|i| {
+            matches!(
+                (i.kind, i.state.as_str()),
+                ("pull_request", _) | ("review", "reviewed") | ("issue", "opened")
+            )
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.time));
+    for item in items {
+        if let Some(url) = item.url {
+            writeln!(
+                w,
+                "{} {}: {} {}: {}",
+                url,
+                item.repo,
+                item.state,
+                item.kind.replace('_', " "),
+                item.title.trim()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Render the report as plain text, with links as `title <url>`; or with
+/// `terminal` set, with ANSI colors and OSC 8 hyperlinks instead.
+fn print_events_text(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+    terminal: bool,
+) -> std::io::Result<()> {
+    let emoji = |e: &'static str| if opts.no_emoji { "" } else { e };
+    let style = |sgr: &str, text: &str| {
+        if terminal {
+            format!("\x1b[{}m{}\x1b[0m", sgr, text)
+        } else {
+            text.to_string()
+        }
+    };
+    let hyperlink = |url: &str, text: &str| format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text);
+    let item = |prefix: &str, title: &str, url: &str| {
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{} ", prefix)
+        };
+        if terminal {
+            format!("  - {}{}", prefix, hyperlink(url.trim(), title.trim()))
+        } else {
+            format!("  - {}{} <{}>", prefix, title.trim(), url.trim())
+        }
+    };
+    for (heading, repos) in repo_groups(events, opts) {
+        if let Some(heading) = heading {
+            if repos.is_empty() {
+                continue;
+            }
+            writeln!(w, "{}", style("1;4", heading))?;
+            writeln!(w)?;
+        }
+        for (repo, events) in repos {
+            let marker = if events.first_contribution {
+                format!(" ({}first contribution)", emoji("🎉 "))
+            } else {
+                String::new()
+            };
+            let name = if terminal {
+                hyperlink(&format!("https://github.com/{}", repo), repo)
+            } else {
+                repo.to_string()
+            };
+            writeln!(
+                w,
+                "{}{}{}",
+                style("1;34", &name),
+                marker,
+                events.unavailable_note()
+            )?;
+            for c in categories(events, opts) {
+                writeln!(w, "{}", style("36", &format!("{}:", c.heading)))?;
+                for i in c.items {
+                    writeln!(w, "{}{}", item(emoji(i.icon), i.title, i.url), i.suffix)?;
+                    if let Some(body) = i.excerpt {
+                        writeln!(w, "      \"{}\"", body)?;
+                    }
+                }
+                if let Some(more) = c.more {
+                    writeln!(w, "  - {}", more)?;
+                }
+            }
+            if events.pushed > 0 {
+                writeln!(
+                    w,
+                    "Pushed {} times",
+                    format_count(events.pushed, opts.locale)
+                )?;
+            }
+            for (pusher, n) in events.pushed_by_others.iter() {
+                writeln!(
+                    w,
+                    "Authored {}, pushed by {}",
+                    plural(*n as usize, "commit"),
+                    pusher
+                )?;
+            }
+            writeln!(w)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_front_matter(
+    w: &mut impl Write,
+    user: &str,
+    start: &chrono::DateTime<Local>,
+    end: &chrono::DateTime<Local>,
+    events: &RepoEventParseData,
+) -> std::io::Result<()> {
+    let t = events.totals();
+    writeln!(w, "---")?;
+    writeln!(w, "user: {}", user)?;
+    writeln!(w, "start: {}", start.to_rfc3339())?;
+    writeln!(w, "end: {}", end.to_rfc3339())?;
+    writeln!(w, "totals:")?;
+    writeln!(w, "  repos: {}", t.repos)?;
+    writeln!(w, "  pull_requests: {}", t.pull_requests)?;
+    writeln!(w, "  reviews: {}", t.reviews)?;
+    writeln!(w, "  issues: {}", t.issues)?;
+    writeln!(w, "  pushes: {}", t.pushes)?;
+    writeln!(w, "---")?;
+    Ok(())
+}
+
+/// Front matter making the report a static site generator post, tagged
+/// with the repositories it covers.
+fn print_post_front_matter(
+    w: &mut impl Write,
+    user: &str,
+    end: &chrono::DateTime<Local>,
+    events: &RepoEventParseData,
+) -> std::io::Result<()> {
+    let title = format!("Status for {} {}", user, end.format("%Y-%m-%d"));
+    let tags: Vec<&str> = events
+        .repos
+        .iter()
+        .filter(|(_, e)| e.activity() > 0)
+        .map(|(repo, _)| repo.as_str())
+        .collect();
+    writeln!(w, "---")?;
+    // JSON strings and arrays are valid YAML flow scalars/sequences.
+    writeln!(w, "title: {}", serde_json::to_string(&title)?)?;
+    writeln!(w, "date: {}", end.to_rfc3339())?;
+    writeln!(w, "tags: {}", serde_json::to_string(&tags)?)?;
+    writeln!(w, "---")?;
+    Ok(())
+}
+
+/// Escape text for use inside a Markdown table cell.
+fn table_cell(s: &str) -> String {
+    s.trim().replace('|', "\\|")
+}
+
+fn parse_day_start(s: &str) -> std::result::Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| format!("Expected HH:MM: {}", s))
+}
+
+fn parse_locale(s: &str) -> std::result::Result<chrono::Locale, String> {
+    s.parse::<chrono::Locale>()
+        .map_err(|_| format!("Unknown locale: {}", s))
+}
+
+fn format_time(t: Option<&chrono::DateTime<Utc>>, locale: Option<chrono::Locale>) -> String {
+    t.map(|t| {
+        let t = t.with_timezone(&Local);
+        match locale {
+            Some(l) => t.format_localized("%x %H:%M", l).to_string(),
+            None => t.format("%Y-%m-%d %H:%M").to_string(),
+        }
+    })
+    .unwrap_or_default()
+}
+
+/// The report's "Events from ... to ..." line.
+fn window_header(
+    start: &DateTime<Local>,
+    end: &DateTime<Local>,
+    locale: Option<chrono::Locale>,
+) -> String {
+    match locale {
+        Some(l) => format!(
+            "Events from {} to {}",
+            start.format_localized("%c", l),
+            end.format_localized("%c", l)
+        ),
+        None => format!("Events from {} to {}", start, end),
+    }
+}
+
+/// Format a count with the locale's digit grouping.
+fn format_count(n: u32, locale: Option<chrono::Locale>) -> String {
+    let sep = match locale {
+        Some(l) => pure_rust_locales::locale_match!(l => LC_NUMERIC::THOUSANDS_SEP),
+        None => return n.to_string(),
+    };
+    let digits = n.to_string();
+    let mut r = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            r.push_str(sep);
+        }
+        r.push(c);
+    }
+    r
+}
+
+/// Render one Markdown table per repository.
+fn print_events_table(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    writeln!(
+        w,
+        "<!-- before: {} after: {} -->",
+        events.before, events.after
+    )?;
+    for (heading, repos) in repo_groups(events, opts) {
+        if let Some(heading) = heading {
+            if repos.is_empty() {
+                continue;
+            }
+            writeln!(w, "## {}", heading)?;
+            writeln!(w)?;
+        }
+        for (repo, events) in repos {
+            print_repo_table(w, repo, events, opts)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_repo_table(
+    w: &mut impl Write,
+    repo: &str,
+    events: &RepoEvents,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    if events.activity() == 0 {
+        return Ok(());
+    }
+    let url = format!("https://github.com/{}", repo);
+    writeln!(w, "### {}", link(url.as_str(), repo))?;
+    writeln!(w)?;
+    writeln!(w, "| Type | Item | State | Time |")?;
+    writeln!(w, "| --- | --- | --- | --- |")?;
+    for c in categories(events, opts) {
+        for i in c.items {
+            let mut item = link(i.url, table_cell(i.title));
+            if let Some(body) = i.excerpt {
+                item.push_str(&format!("<br>{}", markdown_escape(&body)));
+            }
+            let t = format_time(events.times.get(i.url), opts.locale);
+            writeln!(
+                w,
+                "| {} | {} | {}{} | {} |",
+                c.kind, item, i.state, i.suffix, t
+            )?;
+        }
+        if let Some(more) = c.more {
+            writeln!(w, "| {} | {} | | |", c.kind, more)?;
+        }
+    }
+    if events.pushed > 0 {
+        let t = format_time(events.last_push.as_ref(), opts.locale);
+        let n = format_count(events.pushed, opts.locale);
+        writeln!(w, "| Push | {} pushes | | {} |", n, t)?;
+    }
+    for (pusher, n) in events.pushed_by_others.iter() {
+        writeln!(
+            w,
+            "| Push | {} | authored, pushed by {} | |",
+            plural(*n as usize, "commit"),
+            pusher
+        )?;
+    }
+    writeln!(w)?;
+    Ok(())
+}
+
+/// `n thing` or `n things`.
+fn plural(n: usize, thing: &str) -> String {
+    if n == 1 {
+        format!("1 {}", thing)
+    } else if thing.ends_with("sh") {
+        format!("{} {}es", n, thing)
+    } else {
+        format!("{} {}s", n, thing)
+    }
+}
+
+/// `today: 2 PRs · 3 reviews · 5 comments`
+fn brief(period: &str, events: &RepoEventParseData) -> String {
+    let period = if period == "day" { "today" } else { period };
+    format!("{}: {}", period, brief_counts(events))
+}
+
+/// `2 PRs · 3 reviews · 5 comments`, or `no activity`.
+fn brief_counts(events: &RepoEventParseData) -> String {
+    let t = events.totals();
+    let parts: Vec<String> = [
+        (t.pull_requests, "PR"),
+        (t.reviews, "review"),
+        (t.comments as usize, "comment"),
+        (t.pushes as usize, "push"),
+    ]
+    .iter()
+    .filter(|(n, _)| *n > 0)
+    .map(|(n, thing)| plural(*n, thing))
+    .collect();
+    if parts.is_empty() {
+        "no activity".to_string()
+    } else {
+        parts.join(" · ")
+    }
+}
+
+/// Output for a Waybar custom module (`"return-type": "json"`).
+#[derive(Debug, Serialize)]
+struct WaybarModule {
+    text: String,
+    /// Pango markup
+    tooltip: String,
+    /// `active` or `idle`, for styling
+    class: &'static str,
+}
+
+/// Join quoted titles as `"a", "b" and "c"`, or `"a", "b" and 3 more`
+/// when `rest` were left out.
+fn join_titles<'a>(titles: impl Iterator<Item = &'a str>, rest: usize) -> String {
+    let mut titles: Vec<_> = titles.map(|t| format!("\"{}\"", t.trim())).collect();
+    if rest > 0 {
+        titles.push(format!("{} more", rest));
+    }
+    match titles.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// Render each repository as a sentence, for pasting into documents.
+fn print_events_narrative(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    for (repo, events) in events.repos.iter() {
+        if events.activity() == 0 {
+            continue;
+        }
+        let mut clauses = Vec::new();
+        for c in categories(events, opts) {
+            let titles = |c: Category| {
+                let rest = c.len - c.items.len();
+                join_titles(c.items.iter().map(|i| i.title), rest)
+            };
+            match c.kind {
+                "Review" => {
+                    let approved = events
+                        .reviewed
+                        .values()
+                        .filter(|r| matches!(r, ReviewReaction::Approved))
+                        .count();
+                    let n = plural(c.len, "pull request");
+                    clauses.push(if approved == c.len {
+                        format!("reviewed and approved {}", n)
+                    } else if approved > 0 {
+                        format!("reviewed {} (approving {})", n, approved)
+                    } else {
+                        format!("reviewed {}", n)
+                    });
+                }
+                "Pull Request" => clauses.push(format!(
+                    "opened {}: {}",
+                    plural(c.len, "pull request"),
+                    titles(c)
+                )),
+                _ => {
+                    let verb = match c.items[0].state {
+                        "commented" => "commented on",
+                        v => v,
+                    };
+                    clauses.push(format!(
+                        "{} {}: {}",
+                        verb,
+                        plural(c.len, "issue"),
+                        titles(c)
+                    ));
+                }
+            }
+        }
+        if events.pushed > 0 {
+            clauses.push(format!("pushed {}", plural(events.pushed as usize, "time")));
+        }
+        for (pusher, n) in events.pushed_by_others.iter() {
+            clauses.push(format!(
+                "authored {} pushed by {}",
+                plural(*n as usize, "commit"),
+                pusher
+            ));
+        }
+        writeln!(w, "- In {}, {}.", repo, clauses.join("; "))?;
+    }
+    Ok(())
+}
+
+/// The structured report handed to external tools.
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    user: &'a str,
+    start: &'a chrono::DateTime<Local>,
+    end: &'a chrono::DateTime<Local>,
+    #[serde(flatten)]
+    events: &'a RepoEventParseData,
+}
+
+/// A `Command` running `cmd` via the platform shell.
+fn shell_command(cmd: &str) -> std::process::Command {
+    if cfg!(windows) {
+        let mut c = std::process::Command::new("cmd");
+        c.args(["/C", cmd]);
+        c
+    } else {
+        let mut c = std::process::Command::new("sh");
+        c.args(["-c", cmd]);
+        c
+    }
+}
+
+/// Run `cmd` via the shell with `report` as JSON on stdin, returning its stdout.
+fn run_summarize_cmd(cmd: &str, report: &JsonReport) -> Result<String> {
+    let out = pipe_through(cmd, &serde_json::to_vec(report)?)?;
+    Ok(String::from_utf8(out)?)
+}
+
+/// Run `cmd` via the shell with `input` on stdin, returning its stdout.
+fn pipe_through(cmd: &str, input: &[u8]) -> Result<Vec<u8>> {
+    use std::process::Stdio;
+    let mut child = shell_command(cmd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // Written from a thread so a command producing lots of output can't deadlock us
+    let mut stdin = child.stdin.take().expect("stdin");
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let out = child.wait_with_output()?;
+    writer.join().expect("stdin writer")?;
+    if !out.status.success() {
+        anyhow::bail!("Command `{}` failed: {}", cmd, out.status);
+    }
+    Ok(out.stdout)
+}
+
+/// Open a URL or file with the desktop's default application.
+fn desktop_open(target: &str) -> Result<()> {
+    use std::process::Stdio;
+    let mut cmd = if cfg!(windows) {
+        // `start` takes its first quoted argument as the window title.
+        let mut cmd = std::process::Command::new("cmd");
+        cmd.args(["/c", "start", ""]);
+        cmd
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    cmd.arg(target)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Show a desktop notification; on Linux, clicking it opens `report`.
+/// This waits for the notification to be clicked or expire.
+fn notify(summary: &str, report: Option<&str>) -> Result<()> {
+    const TITLE: &str = "GitHub activity";
+    if cfg!(target_os = "macos") {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let script = format!(
+            "display notification {} with title {}",
+            quote(summary),
+            quote(TITLE)
+        );
+        let status = std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .status()
+            .map_err(|e| anyhow::anyhow!("osascript: {}", e))?;
+        if !status.success() {
+            anyhow::bail!("osascript failed: {}", status);
+        }
+        return Ok(());
+    }
+    if cfg!(windows) {
+        let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+        let script = format!(
+            "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+             $t = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+             $x = $t.GetElementsByTagName('text'); \
+             $x.Item(0).AppendChild($t.CreateTextNode({})) > $null; \
+             $x.Item(1).AppendChild($t.CreateTextNode({})) > $null; \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('mygithubstatus').Show([Windows.UI.Notifications.ToastNotification]::new($t))",
+            quote(TITLE),
+            quote(summary)
+        );
+        let status = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .status()
+            .map_err(|e| anyhow::anyhow!("powershell: {}", e))?;
+        if !status.success() {
+            anyhow::bail!("powershell failed: {}", status);
+        }
+        return Ok(());
+    }
+    let mut cmd = std::process::Command::new("notify-send");
+    cmd.arg("--app-name=mygithubstatus");
+    if report.is_some() {
+        cmd.arg("--action=default=Open report");
+    }
+    let out = cmd
+        .args([TITLE, summary])
+        .output()
+        .map_err(|e| anyhow::anyhow!("notify-send: {}", e))?;
+    if !out.status.success() {
+        anyhow::bail!("notify-send failed: {}", out.status);
+    }
+    if let Some(report) = report {
+        if String::from_utf8_lossy(&out.stdout).trim() == "default" {
+            desktop_open(report)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct OwnedRepo {
+    full_name: String,
+    fork: bool,
+}
+
+/// Repositories owned by `user`, most recently pushed first (first page only).
+async fn owned_repos(client: &github_v3::Client, user: &str) -> Result<Vec<String>> {
+    let repos: Vec<OwnedRepo> = client
+        .get()
+        .path("users")
+        .arg(user)
+        .path("repos")
+        .query("type=owner&sort=pushed&per_page=30")
+        .send()
+        .await?
+        .obj()
+        .await?;
+    Ok(repos
+        .into_iter()
+        .filter(|r| !r.fork)
+        .map(|r| r.full_name)
+        .collect())
+}
+
+/// A pull request or issue opened by someone else.
+#[derive(Debug)]
+struct ReceivedItem {
+    url: String,
+    title: String,
+    author: String,
+    /// GitHub considers this the author's first contribution to the repository
+    first_time: bool,
+}
+
+/// Activity by others on one of my repositories.
+#[derive(Debug, Default)]
+struct ReceivedActivity {
+    pull_requests: Vec<ReceivedItem>,
+    issues: Vec<ReceivedItem>,
+    contributors: std::collections::BTreeSet<String>,
+}
+
+/// Summarize pull requests and issues opened by others in `repos`.
+fn received_activity<'a>(
+    events: impl IntoIterator<Item = &'a Event>,
+    repos: &[String],
+    logins: &[&str],
+    start: &chrono::DateTime<Local>,
+    end: &chrono::DateTime<Local>,
+) -> BTreeMap<String, ReceivedActivity> {
+    let mut r: BTreeMap<String, ReceivedActivity> = BTreeMap::new();
+    for e in events {
+        if e.created_at < *start
+            || e.created_at > *end
+            || e.payload.action.as_deref() != Some("opened")
+            || logins
+                .iter()
+                .any(|l| l.eq_ignore_ascii_case(&e.actor.login))
+            || !repos.iter().any(|r| r.eq_ignore_ascii_case(&e.repo.name))
+        {
+            continue;
+        }
+        let (url, title, association) =
+            match (e.typ.as_str(), &e.payload.pull_request, &e.payload.issue) {
+                ("PullRequestEvent", Some(pr), _) => {
+                    (&pr.html_url, &pr.title, &pr.author_association)
+                }
+                ("IssuesEvent", _, Some(issue)) => {
+                    (&issue.html_url, &issue.title, &issue.author_association)
+                }
+                _ => continue,
+            };
+        let item = ReceivedItem {
+            url: url.clone(),
+            title: title.clone(),
+            author: e.actor.login.clone(),
+            first_time: matches!(
+                association.as_deref(),
+                Some("FIRST_TIME_CONTRIBUTOR") | Some("FIRST_TIMER")
+            ),
+        };
+        let activity = r.entry(e.repo.name.clone()).or_default();
+        activity.contributors.insert(item.author.clone());
+        if e.typ == "PullRequestEvent" {
+            activity.pull_requests.push(item);
+        } else {
+            activity.issues.push(item);
+        }
+    }
+    r
+}
+
+fn print_received_item(w: &mut impl Write, item: &ReceivedItem) -> std::io::Result<()> {
+    let marker = if item.first_time {
+        " 🌱 first-time contributor"
+    } else {
+        ""
+    };
+    writeln!(
+        w,
+        "  - {} by @{}{}",
+        link(&item.url, &item.title),
+        item.author,
+        marker
+    )
+}
+
+fn print_received(
+    w: &mut impl Write,
+    received: &BTreeMap<String, ReceivedActivity>,
+) -> std::io::Result<()> {
+    for (repo, activity) in received.iter() {
+        let url = format!("https://github.com/{}", repo);
+        writeln!(w, "### {}", link(url.as_str(), repo))?;
+        if !activity.pull_requests.is_empty() {
+            writeln!(w, "Pull Requests opened: ")?;
+            for item in activity.pull_requests.iter() {
+                print_received_item(w, item)?;
+            }
+            writeln!(w)?;
+        }
+        if !activity.issues.is_empty() {
+            writeln!(w, "Issues filed: ")?;
+            for item in activity.issues.iter() {
+                print_received_item(w, item)?;
+            }
+            writeln!(w)?;
+        }
+        let contributors: Vec<_> = activity
+            .contributors
+            .iter()
+            .map(|c| format!("@{}", c))
+            .collect();
+        writeln!(w, "Contributors: {}", contributors.join(", "))?;
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// For items opened by others in the window, the delay until one of
+/// `logins` first responded (or `None` if they haven't yet).
+fn response_times(
+    events: &[Event],
+    logins: &[&str],
+    start: &chrono::DateTime<Local>,
+    end: &chrono::DateTime<Local>,
+) -> BTreeMap<String, Option<chrono::Duration>> {
+    let is_mine = |e: &Event| {
+        logins
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(&e.actor.login))
+    };
+    let item_url = |e: &Event| {
+        e.payload
+            .pull_request
+            .as_ref()
+            .map(|pr| pr.html_url.clone())
+            .or_else(|| e.payload.issue.as_ref().map(|i| i.html_url.clone()))
+    };
+    let mut opened = BTreeMap::new();
+    for e in events {
+        if e.created_at < *start || e.created_at > *end || is_mine(e) {
+            continue;
+        }
+        if e.payload.action.as_deref() == Some("opened") {
+            if let Some(url) = item_url(e) {
+                opened.insert(url, e.created_at);
+            }
+        }
+    }
+    let mut r: BTreeMap<String, Option<chrono::Duration>> =
+        opened.keys().map(|u| (u.clone(), None)).collect();
+    for e in events {
+        if !is_mine(e) {
+            continue;
+        }
+        let url = match item_url(e) {
+            Some(u) => u,
+            None => continue,
+        };
+        if let (Some(opened_at), Some(slot)) = (opened.get(&url), r.get_mut(&url)) {
+            let delay = e.created_at - *opened_at;
+            if delay >= chrono::Duration::zero() && slot.map(|d| delay < d).unwrap_or(true) {
+                *slot = Some(delay);
+            }
+        }
+    }
+    r
+}
+
+fn format_duration(d: chrono::Duration) -> String {
+    if d.num_hours() >= 48 {
+        format!("{}d", d.num_days())
+    } else if d.num_minutes() >= 60 {
+        format!("{}h{:02}m", d.num_hours(), d.num_minutes() % 60)
+    } else {
+        format!("{}m", d.num_minutes())
+    }
+}
+
+/// Format a byte count with a binary unit, e.g. `1.5 MiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn cache_command(opt: &Opt, cmd: &CacheCommand) -> Result<()> {
+    let cache = cache::ReportCache::new(opt.cache_ttl).ok_or_else(|| {
+        anyhow::anyhow!("no cache directory could be determined; set MYGHSTATUS_DATA_DIR")
+    })?;
+    match cmd {
+        CacheCommand::Path => println!("{}", cache.dir().display()),
+        CacheCommand::Status => {
+            let entries = cache::entries(cache.dir())?;
+            let ttl = std::time::Duration::from_secs(opt.cache_ttl);
+            let expired = entries
+                .iter()
+                .filter(|e| e.modified.elapsed().is_ok_and(|a| a > ttl))
+                .count();
+            println!("{}", cache.dir().display());
+            println!(
+                "{} entries ({} expired), {}",
+                entries.len(),
+                expired,
+                format_size(entries.iter().map(|e| e.size).sum())
+            );
+        }
+        CacheCommand::Prune => {
+            let n = cache.prune(chrono::Duration::seconds(opt.cache_ttl as i64))?;
+            println!("Removed {} expired entries", n);
+        }
+        CacheCommand::Clear => {
+            let n = cache.clear()?;
+            println!("Removed {} entries", n);
+        }
+    }
+    Ok(())
+}
+
+fn print_response_times(
+    w: &mut impl Write,
+    times: &BTreeMap<String, Option<chrono::Duration>>,
+) -> std::io::Result<()> {
+    for (url, d) in times.iter() {
+        let d = d
+            .map(format_duration)
+            .unwrap_or_else(|| "no response".into());
+        writeln!(w, "  - {} {}", url, d)?;
+    }
+    let answered: Vec<_> = times.values().flatten().copied().collect();
+    let n = answered.len();
+    match median(answered) {
+        None => writeln!(w, "No responses in this window")?,
+        Some(m) => writeln!(
+            w,
+            "Median response time: {} ({} of {} answered)",
+            format_duration(m),
+            n,
+            times.len()
+        )?,
+    }
+    Ok(())
+}
+
+/// The median of `durations`, averaging the middle two if there's an even
+/// number of them.
+fn median(mut durations: Vec<chrono::Duration>) -> Option<chrono::Duration> {
+    durations.sort();
+    let n = durations.len();
+    match n {
+        0 => None,
+        _ if n % 2 == 1 => Some(durations[n / 2]),
+        _ => Some((durations[n / 2 - 1] + durations[n / 2]) / 2),
+    }
+}
+
+/// Common words that say nothing about the topic of a title.
+const STOPWORDS: &[&str] = &[
+    "a", "add", "an", "and", "are", "as", "at", "be", "by", "can", "do", "don", "for", "from",
+    "in", "into", "is", "it", "make", "not", "of", "on", "or", "the", "this", "to", "use", "when",
+    "with",
+];
+
+/// Print `f`'s output for each window, headed by the window when there
+/// are several.
+fn print_each_window<W: Write>(
+    out: &mut W,
+    windows: &[Window],
+    events: &[RepoEventParseData],
+    locale: Option<chrono::Locale>,
+    mut f: impl FnMut(&mut W, &RepoEventParseData) -> Result<()>,
+) -> Result<()> {
+    for (i, (w, events)) in windows.iter().zip(events).enumerate() {
+        if windows.len() > 1 {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "{}", window_header(&w.start, &w.end, locale))?;
+        }
+        f(out, events)?;
+    }
+    Ok(())
+}
+
+/// Print the most common title terms for each repository.
+fn print_topics(w: &mut impl Write, events: &RepoEventParseData, top: usize) -> Result<()> {
+    for (repo, events) in events.repos.iter() {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for title in events.titles.values() {
+            for word in title.split(|c: char| !c.is_alphanumeric()) {
+                let word = word.to_lowercase();
+                if word.len() < 2
+                    || word.chars().all(|c| c.is_ascii_digit())
+                    || STOPWORDS.contains(&word.as_str())
+                {
+                    continue;
+                }
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+        if counts.is_empty() {
+            continue;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let terms: Vec<_> = counts
+            .iter()
+            .take(top)
+            .map(|(t, n)| format!("{} ({})", t, n))
+            .collect();
+        writeln!(w, "{}: {}", repo, terms.join(", "))?;
+    }
+    Ok(())
+}
+
+/// Count each pull request, review, issue and repository's pushes towards
+/// the first objective with a matching rule, and print each objective's share.
+fn print_okrs(w: &mut impl Write, events: &RepoEventParseData, rules: &[OkrRule]) -> Result<()> {
+    const UNASSIGNED: &str = "Unassigned";
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for r in rules {
+        if !counts.iter().any(|(n, _)| *n == r.name) {
+            counts.push((r.name.as_str(), 0));
+        }
+    }
+    counts.push((UNASSIGNED, 0));
+    let mut total = 0;
+    for (repo, events) in events.repos.iter() {
+        let urls = events
+            .pr_action
+            .keys()
+            .chain(events.reviewed.keys())
+            .chain(events.issues.keys())
+            .map(|u| Some(u.as_str()));
+        let pushes = (events.pushed > 0).then_some(None);
+        for url in urls.chain(pushes) {
+            let name = rules
+                .iter()
+                .find(|r| r.matches(repo, events, url))
+                .map(|r| r.name.as_str())
+                .unwrap_or(UNASSIGNED);
+            if let Some(c) = counts.iter_mut().find(|(n, _)| *n == name) {
+                c.1 += 1;
+            }
+            total += 1;
+        }
+    }
+    for (name, n) in counts {
+        if name == UNASSIGNED && n == 0 {
+            continue;
+        }
+        let pct = (n * 100).checked_div(total).unwrap_or(0);
+        writeln!(w, "{}: {} ({}%)", name, plural(n, "item"), pct)?;
+    }
+    Ok(())
+}
+
+/// Escape text for inclusion in XML/HTML.
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write horizontal bar charts of activity per day and per repository.
+fn write_chart_svg(w: &mut impl Write, events: &RepoEventParseData) -> std::io::Result<()> {
+    const WIDTH: usize = 640;
+    const LABEL: usize = 220;
+    const BAR: usize = 18;
+    let days: Vec<(String, usize)> = events
+        .by_day
+        .iter()
+        .map(|(d, n)| (d.format("%a %Y-%m-%d").to_string(), *n as usize))
+        .collect();
+    let repos: Vec<(String, usize)> = events
+        .repos
+        .iter()
+        .filter(|(_, e)| e.activity() > 0)
+        .map(|(r, e)| (r.clone(), e.activity()))
+        .collect();
+    let charts = [("Activity by day", days), ("Activity by repository", repos)];
+    let height: usize = charts.iter().map(|(_, rows)| (rows.len() + 2) * BAR).sum();
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="12">"#,
+        WIDTH, height
+    )?;
+    let mut y = 0;
+    for (title, rows) in charts.iter() {
+        y += BAR;
+        writeln!(
+            w,
+            r#"  <text x="0" y="{}" font-weight="bold">{}</text>"#,
+            y - 4,
+            title
+        )?;
+        let max = rows.iter().map(|(_, n)| *n).max().unwrap_or(1).max(1);
+        for (label, n) in rows.iter() {
+            let len = (WIDTH - LABEL - 40) * n / max;
+            writeln!(
+                w,
+                r#"  <text x="0" y="{}">{}</text>"#,
+                y + BAR - 5,
+                xml_escape(label)
+            )?;
+            writeln!(
+                w,
+                r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#2f81f7"/>"##,
+                LABEL,
+                y + 2,
+                len,
+                BAR - 4
+            )?;
+            writeln!(
+                w,
+                r#"  <text x="{}" y="{}">{}</text>"#,
+                LABEL + len + 4,
+                y + BAR - 5,
+                n
+            )?;
+            y += BAR;
+        }
+        y += BAR;
+    }
+    writeln!(w, "</svg>")?;
+    Ok(())
+}
+
+/// A grid of days from `first` to `last`, a column per week and a row per
+/// weekday, shaded by the number of events like GitHub's contribution graph.
+fn write_heatmap_svg(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> std::io::Result<()> {
+    const CELL: usize = 12;
+    const STEP: usize = CELL + 3;
+    const LABEL: usize = 30;
+    const COLORS: [&str; 5] = ["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"];
+    let monday = first.week(Weekday::Mon).first_day();
+    let weeks = (last - monday).num_days() as usize / 7 + 1;
+    let max = events.by_day.values().copied().max().unwrap_or(0).max(1) as usize;
+    let (width, height) = (LABEL + weeks * STEP, 7 * STEP);
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="10">"#,
+        width, height
+    )?;
+    for (row, name) in [(0, "Mon"), (2, "Wed"), (4, "Fri")] {
+        writeln!(
+            w,
+            r#"  <text x="0" y="{}">{}</text>"#,
+            row * STEP + CELL - 2,
+            name
+        )?;
+    }
+    for day in first.iter_days().take_while(|d| *d <= last) {
+        let n = events.by_day.get(&day).copied().unwrap_or(0) as usize;
+        // Level 0 is reserved for no activity at all.
+        let level = if n == 0 { 0 } else { (4 * n).div_ceil(max) };
+        let col = (day - monday).num_days() as usize / 7;
+        let row = day.weekday().num_days_from_monday() as usize;
+        writeln!(
+            w,
+            r#"  <rect x="{}" y="{}" width="{CELL}" height="{CELL}" rx="2" fill="{}"><title>{}: {}</title></rect>"#,
+            LABEL + col * STEP,
+            row * STEP,
+            COLORS[level],
+            day.format("%a %Y-%m-%d"),
+            plural(n, "event")
+        )?;
+    }
+    writeln!(w, "</svg>")?;
+    Ok(())
+}
+
+/// Render the report in Confluence storage format (XHTML).
+fn print_events_confluence(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    let a = |url: &str, title: &str| {
+        format!(
+            "<a href=\"{}\">{}</a>",
+            xml_escape(url.trim()),
+            xml_escape(title.trim())
+        )
+    };
+    for (repo, events) in events.repos.iter() {
+        if events.activity() == 0 {
+            continue;
+        }
+        let url = format!("https://github.com/{}", repo);
+        writeln!(w, "<h3>{}</h3>", a(&url, repo))?;
+        for c in categories(events, opts) {
+            writeln!(w, "<p>{}:</p><ul>", c.heading)?;
+            for i in c.items {
+                let quote = i
+                    .excerpt
+                    .map(|body| format!("<blockquote>{}</blockquote>", xml_escape(&body)))
+                    .unwrap_or_default();
+                let item = a(i.url, i.title);
+                writeln!(w, "<li>{} {}{}{}</li>", i.icon, item, i.suffix, quote)?;
+            }
+            if let Some(more) = c.more {
+                writeln!(w, "<li>{}</li>", more)?;
+            }
+            writeln!(w, "</ul>")?;
+        }
+        if events.pushed > 0 {
+            writeln!(w, "<p>Pushed {} times</p>", events.pushed)?;
+        }
+        for (pusher, n) in events.pushed_by_others.iter() {
+            writeln!(
+                w,
+                "<p>Authored {}, pushed by {}</p>",
+                plural(*n as usize, "commit"),
+                xml_escape(pusher)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The `id` used to link to a repository's section of the HTML report.
+fn html_anchor(repo: &str) -> String {
+    repo.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Render the body of an HTML report: a table of contents followed by a
+/// section per repository.
+fn print_events_html(
+    w: &mut impl Write,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+) -> std::io::Result<()> {
+    let a = |url: &str, title: &str| {
+        format!(
+            "<a href=\"{}\">{}</a>",
+            xml_escape(url.trim()),
+            xml_escape(title.trim())
+        )
+    };
+    // Screen readers announce emoji by their Unicode name, which says
+    // nothing about what they mean here.
+    let icon = |emoji: &str, meaning: &str| {
+        if opts.no_emoji_html {
+            format!("<span class=\"badge\">{}</span>", meaning)
+        } else {
+            format!(
+                "<span role=\"img\" aria-label=\"{}\">{}</span>",
+                meaning, emoji
+            )
+        }
+    };
+    let groups = repo_groups(events, opts);
+    let collapsed = collapsed_repos(events, opts);
+    // Don't skip heading levels when there are no group headings.
+    let level = if opts.mine.is_some() { 3 } else { 2 };
+    writeln!(w, "<nav aria-label=\"Repositories\"><ul>")?;
+    for (_, repos) in groups.iter() {
+        for (repo, _) in repos.iter() {
+            writeln!(
+                w,
+                "<li><a href=\"#{}\">{}</a></li>",
+                html_anchor(repo),
+                xml_escape(repo)
+            )?;
+        }
+    }
+    writeln!(w, "</ul></nav>")?;
+    for (heading, repos) in groups {
+        if let Some(heading) = heading {
+            if repos.is_empty() {
+                continue;
+            }
+            writeln!(w, "<h2>{}</h2>", heading)?;
+        }
+        for (repo, events) in repos {
+            let category = |name: &str| format!("<h{0}>{1}</h{0}>", level + 1, name);
+            let url = format!("https://github.com/{}", repo);
+            let collapse = collapsed.contains(repo.as_str());
+            writeln!(w, "<section id=\"{}\">", html_anchor(repo))?;
+            if collapse {
+                writeln!(
+                    w,
+                    "<details><summary>{} ({})</summary>",
+                    a(&url, repo),
+                    events.activity()
+                )?;
+            } else {
+                let marker = if events.first_contribution {
+                    format!(" {} first contribution", icon("🎉", "celebration"))
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    w,
+                    "<h{0}>{1}{2}{3}</h{0}>",
+                    level,
+                    a(&url, repo),
+                    marker,
+                    xml_escape(&events.unavailable_note())
+                )?;
+            }
+            for c in categories(events, opts) {
+                writeln!(w, "{}<ul>", category(c.heading))?;
+                for i in c.items {
+                    let quote = i
+                        .excerpt
+                        .map(|body| format!("<blockquote>{}</blockquote>", xml_escape(&body)))
+                        .unwrap_or_default();
+                    writeln!(
+                        w,
+                        "<li>{} {}{}{}</li>",
+                        icon(i.icon, i.meaning),
+                        a(i.url, i.title),
+                        i.suffix,
+                        quote
+                    )?;
+                }
+                if let Some(more) = c.more {
+                    writeln!(w, "<li>{}</li>", more)?;
+                }
+                writeln!(w, "</ul>")?;
+            }
+            if events.pushed > 0 {
+                let n = format_count(events.pushed, opts.locale);
+                writeln!(w, "<p>Pushed {} times</p>", n)?;
+            }
+            for (pusher, n) in events.pushed_by_others.iter() {
+                writeln!(
+                    w,
+                    "<p>Authored {}, pushed by {}</p>",
+                    plural(*n as usize, "commit"),
+                    xml_escape(pusher)
+                )?;
+            }
+            if collapse {
+                writeln!(w, "</details>")?;
+            }
+            writeln!(w, "</section>")?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordField {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordEmbed {
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    fields: Vec<DiscordField>,
+}
+
+/// Body for a Discord webhook `POST`.
+#[derive(Debug, Serialize)]
+struct DiscordPayload {
+    embeds: Vec<DiscordEmbed>,
+}
+
+/// Build a Discord embed with a field per repository, staying within
+/// Discord's limits (25 fields, 1024 characters per field).
+fn discord_payload(
+    title: String,
+    description: Option<String>,
+    events: &RepoEventParseData,
+    opts: &RenderOptions,
+) -> DiscordPayload {
+    const MAX_FIELDS: usize = 25;
+    const MAX_VALUE: usize = 1024;
+    let mut fields = Vec::new();
+    for (repo, events) in events.repos.iter() {
+        if events.activity() == 0 {
+            continue;
+        }
+        let mut lines = Vec::new();
+        for c in categories(events, opts) {
+            for i in c.items {
+                lines.push(format!("{} {}{}", i.icon, link(i.url, i.title), i.suffix));
+                if let Some(body) = i.excerpt {
+                    lines.push(format!("> {}", body));
+                }
+            }
+            lines.extend(c.more);
+        }
+        if events.pushed > 0 {
+            lines.push(format!("Pushed {} times", events.pushed));
+        }
+        for (pusher, n) in events.pushed_by_others.iter() {
+            lines.push(format!(
+                "Authored {}, pushed by {}",
+                plural(*n as usize, "commit"),
+                pusher
+            ));
+        }
+        let mut value = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            let more = format!("…and {} more", lines.len() - i);
+            if value.chars().count() + line.chars().count() + more.chars().count() + 2 > MAX_VALUE {
+                value.push_str(&more);
+                break;
+            }
+            value.push_str(line);
+            value.push('\n');
+        }
+        fields.push(DiscordField {
+            name: repo.clone(),
+            value: value.trim_end().to_string(),
+        });
+    }
+    if fields.len() > MAX_FIELDS {
+        let rest = fields.split_off(MAX_FIELDS - 1);
+        fields.push(DiscordField {
+            name: format!("…and {} more repositories", rest.len()),
+            value: rest
+                .iter()
+                .map(|f| f.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+                .chars()
+                .take(MAX_VALUE)
+                .collect(),
+        });
+    }
+    DiscordPayload {
+        embeds: vec![DiscordEmbed {
+            title,
+            description,
+            fields,
+        }],
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfluenceVersion {
+    number: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfluencePage {
+    id: String,
+    version: ConfluenceVersion,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfluenceSearch {
+    results: Vec<ConfluencePage>,
+}
+
+/// A client for requests that don't go through `github_v3`.
+fn http_client() -> Result<reqwest::Client> {
+    let builder = reqwest::Client::builder();
+    #[cfg(feature = "rustls")]
+    let builder = builder.use_rustls_tls();
+    Ok(builder.build()?)
+}
+
+/// Create the page `title` in `space`, or replace its content if it exists.
+async fn publish_confluence(base: &str, space: &str, title: &str, body: &str) -> Result<()> {
+    let token = std::env::var("CONFLUENCE_TOKEN")
+        .map_err(|_| anyhow::anyhow!("CONFLUENCE_TOKEN is required to publish to Confluence"))?;
+    let user = std::env::var("CONFLUENCE_USER").ok();
+    let client = http_client()?;
+    let auth = |req: reqwest::RequestBuilder| match user {
+        Some(ref user) => req.basic_auth(user, Some(&token)),
+        None => req.bearer_auth(&token),
+    };
+    let api = format!("{}/rest/api/content", base.trim_end_matches('/'));
+    let existing: ConfluenceSearch = auth(client.get(&api))
+        .query(&[("spaceKey", space), ("title", title), ("expand", "version")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let storage = serde_json::json!({
+        "storage": { "value": body, "representation": "storage" }
+    });
+    let req = match existing.results.first() {
+        Some(page) => auth(client.put(&format!("{}/{}", api, page.id))).json(&serde_json::json!({
+            "id": page.id,
+            "type": "page",
+            "title": title,
+            "version": { "number": page.version.number + 1 },
+            "body": storage,
+        })),
+        None => auth(client.post(&api)).json(&serde_json::json!({
+            "type": "page",
+            "title": title,
+            "space": { "key": space },
+            "body": storage,
+        })),
+    };
+    req.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// A shields.io endpoint badge, see <https://shields.io/badges/endpoint-badge>.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Badge<'a> {
+    schema_version: u32,
+    label: &'a str,
+    message: String,
+    color: &'a str,
+}
+
+/// Write each repository's part of the report to its own Markdown file.
+fn write_repo_files(
+    dir: &str,
+    opt: &Opt,
+    start: &chrono::DateTime<Local>,
+    end: &chrono::DateTime<Local>,
+    events: &RepoEventParseData,
+) -> Result<()> {
+    let opts = RenderOptions::from_opt(opt);
+    for (repo, events) in events.repos.iter().filter(|(_, e)| e.activity() > 0) {
+        let path = std::path::Path::new(dir).join(format!("{}.md", repo));
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let f = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(opt.append)
+            .truncate(!opt.append)
+            .open(&path)?;
+        let mut w = std::io::BufWriter::new(f);
+        writeln!(w, "{}", window_header(start, end, opt.locale))?;
+        print_repo(&mut w, repo, events, false, &opts)?;
+        w.flush()?;
+    }
+    Ok(())
+}
+
+fn write_badges(dir: &str, events: &RepoEventParseData) -> Result<()> {
+    let t = events.totals();
+    // Issues can also be here for being opened or closed.
+    let commented = events
+        .repos
+        .values()
+        .flat_map(|r| r.issues.values())
+        .filter(|i| i.comments > 0)
+        .count();
+    let dir = std::path::Path::new(dir);
+    std::fs::create_dir_all(dir)?;
+    let badges = [
+        ("pull-requests", "PRs opened", t.pull_requests as u32),
+        ("reviews", "reviews", t.reviews as u32),
+        ("issues", "issues commented", commented as u32),
+        ("pushes", "pushes", t.pushes),
+    ];
+    for (name, label, count) in badges.iter() {
+        let badge = Badge {
+            schema_version: 1,
+            label,
+            message: count.to_string(),
+            color: if *count > 0 { "blue" } else { "lightgrey" },
+        };
+        let f = std::fs::File::create(dir.join(format!("{}.json", name)))?;
+        serde_json::to_writer(std::io::BufWriter::new(f), &badge)?;
+    }
+    Ok(())
+}
+
+const PROFILE_START_MARKER: &str = "<!-- mygithubstatus:start -->";
+const PROFILE_END_MARKER: &str = "<!-- mygithubstatus:end -->";
+
+#[derive(Debug, Deserialize)]
+struct RepoContents {
+    sha: String,
+    content: String,
+}
+
+/// Replace the delimited section of `readme` with `report`.
+fn replace_profile_section(readme: &str, report: &str) -> Result<String> {
+    let start = readme
+        .find(PROFILE_START_MARKER)
+        .ok_or_else(|| anyhow::anyhow!("Missing {} in README", PROFILE_START_MARKER))?
+        + PROFILE_START_MARKER.len();
+    let end = readme[start..]
+        .find(PROFILE_END_MARKER)
+        .ok_or_else(|| anyhow::anyhow!("Missing {} in README", PROFILE_END_MARKER))?
+        + start;
+    Ok(format!(
+        "{}\n{}\n{}",
+        &readme[..start],
+        report.trim(),
+        &readme[end..]
+    ))
+}
+
+/// Update the `user/user` profile README via the contents API.
+async fn update_profile_readme(client: &github_v3::Client, user: &str, report: &str) -> Result<()> {
+    let token = std::env::var("GITHUB_TOKEN")
+        .map_err(|_| anyhow::anyhow!("GITHUB_TOKEN is required to update the profile README"))?;
+    let contents: RepoContents = client
+        .get()
+        .path("repos")
+        .arg(user)
+        .arg(user)
+        .path("contents/README.md")
+        .send()
+        .await?
+        .obj()
+        .await?;
+    let encoded: String = contents.content.split_whitespace().collect();
+    let readme = String::from_utf8(base64::decode(encoded)?)?;
+    let updated = replace_profile_section(&readme, report)?;
+    if updated == readme {
+        return Ok(());
+    }
+    let url = format!(
+        "https://api.github.com/repos/{0}/{0}/contents/README.md",
+        urlencoding::encode(user)
+    );
+    let body = serde_json::json!({
+        "message": "Update activity summary",
+        "content": base64::encode(updated),
+        "sha": contents.sha,
+    });
+    http_client()?
+        .put(&url)
+        .header("Authorization", format!("token {}", token))
+        .header("Accept", "application/vnd.github.v3+json")
+        .header("User-Agent", "mygithubstatus")
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Metadata about a run, written by `--emit-meta` for monitoring.
+#[derive(Debug, Serialize)]
+struct RunMeta<'a> {
+    user: &'a str,
+    start: chrono::DateTime<Local>,
+    end: chrono::DateTime<Local>,
+    from_file: Option<&'a str>,
+    fetch: FetchStats,
+    /// Whether the activity came from the cache rather than the API
+    cache_hit: bool,
+    events_total: u32,
+    events_before: u32,
+    events_after: u32,
+    duration_secs: f64,
+}
+
+/// Wall-clock time spent in each phase of a run, for `--timings`.
+/// A phase may run several times (e.g. once per window); its total is
+/// reported.
+#[derive(Debug, Default)]
+struct Timings {
+    phases: Vec<(&'static str, std::time::Duration)>,
+    running: Option<(&'static str, std::time::Instant)>,
+}
+
+impl Timings {
+    /// Start timing the phase `name`.
+    fn start(&mut self, name: &'static str) {
+        debug_assert!(self.running.is_none(), "phase {} not stopped", name);
+        self.running = Some((name, std::time::Instant::now()));
+    }
+
+    /// Stop timing the phase `name`, adding the time since it started.
+    fn stop(&mut self, name: &'static str) {
+        let Some((running, started)) = self.running.take() else {
+            return;
+        };
+        debug_assert_eq!(running, name);
+        let elapsed = started.elapsed();
+        match self.phases.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, d)) => *d += elapsed,
+            None => self.phases.push((name, elapsed)),
+        }
+    }
+
+    fn print(&self, stats: &FetchStats) {
+        for (name, d) in self.phases.iter() {
+            eprintln!("{:>8}: {:.3}s", name, d.as_secs_f64());
+        }
+        for (i, secs) in stats.page_secs.iter().enumerate() {
+            eprintln!("  page {}: {:.3}s", i + 1, secs);
+        }
+    }
+}
+
+/// Expand the `--output` template variables.
+fn expand_output_template(
+    template: &str,
+    user: &str,
+    day: &chrono::DateTime<Local>,
+    period: &str,
+) -> String {
+    template
+        .replace("{date}", &day.format("%Y-%m-%d").to_string())
+        .replace("{user}", user)
+        .replace("{period}", period)
+}
+
+/// Everything that affects the activity found for a window, but not how
+/// it is rendered, so e.g. `brief` can reuse a report's activity.
+#[derive(Serialize)]
+struct CacheQuery<'a> {
+    user: &'a str,
+    also_login: &'a [String],
+    email: &'a [String],
+    push_repo: &'a [String],
+    /// Hash of the --from-file contents
+    input: Option<String>,
+    label_filter: &'a [String],
+    exclude_label: &'a [String],
+    milestone: &'a Option<String>,
+    search_backfill: bool,
+    resolve_renames: bool,
+    detect_first_contributions: bool,
+    check_resolved: bool,
+    start: chrono::DateTime<Local>,
+    end: chrono::DateTime<Local>,
+}
+
+fn report_cache_key(opt: &Opt, w: &Window) -> Result<String> {
+    // The input file's contents, so editing it invalidates the cache.
+    let input = match opt.from_file {
+        Some(ref f) => Some(format!("{:016x}", cache::hash(&std::fs::read(f)?))),
+        None => None,
+    };
+    cache::key(&CacheQuery {
+        user: &opt.user,
+        also_login: &opt.also_login,
+        email: &opt.email,
+        push_repo: &opt.push_repo,
+        input,
+        label_filter: &opt.label_filter,
+        exclude_label: &opt.exclude_label,
+        milestone: &opt.milestone,
+        search_backfill: opt.search_backfill,
+        resolve_renames: opt.resolve_renames,
+        detect_first_contributions: opt.detect_first_contributions,
+        check_resolved: opt.check_resolved,
+        start: w.start,
+        end: w.end,
+    })
+}
+
+/// Render just the events of a report in the given format, without the
+/// header lines, front matter or delivery the command line tool adds.
+pub fn render_to_string(
+    events: &RepoEventParseData,
+    format: Format,
+    opts: &RenderOptions,
+) -> Result<String> {
+    let mut w = Vec::new();
+    match format {
+        Format::Markdown | Format::MdTable if opts.digest => {
+            print_events_digest(&mut w, events, opts, true)?
+        }
+        Format::Text | Format::Terminal | Format::Narrative | Format::AsciiDoc if opts.digest => {
+            print_events_digest(&mut w, events, opts, false)?
+        }
+        Format::Markdown => print_events(&mut w, events, opts)?,
+        Format::MdTable => print_events_table(&mut w, events, opts)?,
+        Format::Narrative => print_events_narrative(&mut w, events, opts)?,
+        Format::Text | Format::Email | Format::Waybar => {
+            print_events_text(&mut w, events, opts, false)?
+        }
+        Format::Terminal => print_events_text(&mut w, events, opts, true)?,
+        Format::AsciiDoc => print_events_asciidoc(&mut w, events, opts)?,
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut w, events)?;
+            writeln!(w)?;
+        }
+        Format::Yaml => serde_yaml::to_writer(&mut w, events).map_err(std::io::Error::other)?,
+        Format::Csv => print_events_csv(&mut w, events)?,
+        Format::Html | Format::HtmlFragment => print_events_html(&mut w, events, opts)?,
+        Format::Discord => {
+            let payload = discord_payload("GitHub activity".into(), None, events, opts);
+            serde_json::to_writer_pretty(&mut w, &payload)?;
+            writeln!(w)?;
+        }
+        Format::Atom => print_events_atom(&mut w, "GitHub activity", events)?,
+        Format::Confluence => print_events_confluence(&mut w, events, opts)?,
+        Format::Quickfix => print_events_quickfix(&mut w, events)?,
+        Format::Mermaid => print_events_mermaid(&mut w, events)?,
+    }
+    Ok(String::from_utf8(w)?)
+}
+
+fn truncation_warning(t: &chrono::DateTime<Utc>) -> String {
+    format!(
+        "⚠ GitHub's event feed only reaches back to {}; earlier activity may be missing.",
+        t.with_timezone(&Local)
+    )
+}
+
+/// Render a window's report in one of the standalone formats.
+fn render_standalone(opt: &Opt, w: &Window, events: &RepoEventParseData) -> Result<String> {
+    let user = opt.user.as_str();
+    let (start, end) = (&w.start, &w.end);
+    let title = format!("Status for {} {}", user, end.format("%Y-%m-%d"));
+    let json = JsonReport {
+        user,
+        start,
+        end,
+        events,
+    };
+    if let Some(ref path) = opt.template {
+        return render_template(path, &json);
+    }
+    let r = match opt.format() {
+        Format::Json => serde_json::to_string_pretty(&json)? + "\n",
+        Format::Yaml => serde_yaml::to_string(&json)?,
+        Format::Html => html_page(opt, w, events, &title)?,
+        Format::HtmlFragment => html_body(opt, w, events, &title)?,
+        Format::Email => {
+            let mut text = Vec::new();
+            writeln!(text, "{}", window_header(start, end, opt.locale))?;
+            if let Some(t) = events.truncated_at {
+                writeln!(text, "{}", truncation_warning(&t))?;
+            }
+            if let Some(note) = absence_note(start.date_naive(), end.date_naive(), &opt.absence) {
+                writeln!(text, "{}", note)?;
+            }
+            writeln!(text)?;
+            let render_opts = RenderOptions::from_opt(opt);
+            text.extend_from_slice(
+                render_to_string(events, Format::Text, &render_opts)?.as_bytes(),
+            );
+            let html = html_page(opt, w, events, &title)?;
+            let headers = email_headers(opt.email_from.as_deref(), &opt.email_to, &title)?;
+            email_message(&headers, &String::from_utf8(text)?, &html)
+        }
+        Format::Waybar => {
+            let render_opts = RenderOptions::from_opt(opt);
+            let report = render_to_string(events, opt.format(), &render_opts)?;
+            let module = WaybarModule {
+                text: brief_counts(events),
+                tooltip: xml_escape(report.trim_end()),
+                class: if events.repos.values().any(|e| e.activity() > 0) {
+                    "active"
+                } else {
+                    "idle"
+                },
+            };
+            // Waybar reads one JSON object per line.
+            serde_json::to_string(&module)? + "\n"
+        }
+        Format::Discord => {
+            let mut description = window_header(start, end, opt.locale);
+            if let Some(t) = events.truncated_at {
+                description = format!("{}\n{}", description, truncation_warning(&t));
+            }
+            let render_opts = RenderOptions::from_opt(opt);
+            let payload = discord_payload(title, Some(description), events, &render_opts);
+            serde_json::to_string_pretty(&payload)? + "\n"
+        }
+        Format::Atom => {
+            let mut feed = Vec::new();
+            print_events_atom(&mut feed, &format!("GitHub activity of {}", user), events)?;
+            String::from_utf8(feed)?
+        }
+        f => render_to_string(events, f, &RenderOptions::from_opt(opt))?,
+    };
+    Ok(r)
+}
+
+/// A complete HTML page for the report.
+fn html_page(opt: &Opt, w: &Window, events: &RepoEventParseData, title: &str) -> Result<String> {
+    let mut report = Vec::new();
+    writeln!(report, "<!DOCTYPE html>")?;
+    writeln!(report, "<html lang=\"en\">")?;
+    writeln!(report, "<head>")?;
+    writeln!(report, "<meta charset=\"utf-8\">")?;
+    writeln!(report, "<title>{}</title>", xml_escape(title))?;
+    writeln!(report, "<style>{}</style>", opt.html_theme.css())?;
+    if let Some(ref path) = opt.html_css {
+        let css = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path, e))?;
+        writeln!(report, "<style>\n{}</style>", css.trim_end())?;
+    }
+    writeln!(report, "</head>")?;
+    writeln!(report, "<body>")?;
+    writeln!(report, "<main>")?;
+    report.extend_from_slice(html_body(opt, w, events, title)?.as_bytes());
+    writeln!(report, "</main>")?;
+    writeln!(report, "</body>")?;
+    writeln!(report, "</html>")?;
+    Ok(String::from_utf8(report)?)
+}
+
+/// The contents of an HTML report page: its heading, notes and the report.
+fn html_body(opt: &Opt, w: &Window, events: &RepoEventParseData, title: &str) -> Result<String> {
+    let (start, end) = (&w.start, &w.end);
+    let mut report = Vec::new();
+    writeln!(report, "<h1>{}</h1>", xml_escape(title))?;
+    writeln!(report, "<p>{}</p>", window_header(start, end, opt.locale))?;
+    if let Some(t) = events.truncated_at {
+        writeln!(report, "<p><strong>{}</strong></p>", truncation_warning(&t))?;
+    }
+    if let Some(note) = absence_note(start.date_naive(), end.date_naive(), &opt.absence) {
+        writeln!(report, "<p>{}</p>", xml_escape(&note))?;
+    }
+    let render_opts = RenderOptions::from_opt(opt);
+    report.extend_from_slice(render_to_string(events, Format::Html, &render_opts)?.as_bytes());
+    Ok(String::from_utf8(report)?)
+}
+
+/// `From`, `To` and `Subject` headers for a report sent by email.
+fn email_headers(from: Option<&str>, to: &[String], subject: &str) -> Result<String> {
+    if to.is_empty() {
+        anyhow::bail!("--format email needs --email-to");
+    }
+    let subject = if subject.is_ascii() {
+        subject.to_string()
+    } else {
+        format!("=?utf-8?B?{}?=", base64::encode(subject))
+    };
+    let mut h = String::new();
+    if let Some(from) = from {
+        h.push_str(&format!("From: {}\n", from));
+    }
+    h.push_str(&format!("To: {}\n", to.join(", ")));
+    h.push_str(&format!("Subject: {}\n", subject));
+    Ok(h)
+}
+
+/// An email message with `headers` and the report as both plain text and HTML.
+fn email_message(headers: &str, text: &str, html: &str) -> String {
+    let boundary = format!(
+        "=_mygithubstatus_{:016x}",
+        cache::hash(format!("{}{}", text, html).as_bytes())
+    );
+    let mut m = String::new();
+    m.push_str(headers);
+    m.push_str(&format!("Date: {}\n", Local::now().to_rfc2822()));
+    m.push_str("MIME-Version: 1.0\n");
+    m.push_str(&format!(
+        "Content-Type: multipart/alternative; boundary=\"{}\"\n\n",
+        boundary
+    ));
+    for (subtype, body) in [("plain", text), ("html", html)] {
+        m.push_str(&format!("--{}\n", boundary));
+        m.push_str(&format!("Content-Type: text/{}; charset=utf-8\n", subtype));
+        m.push_str("Content-Transfer-Encoding: 8bit\n\n");
+        m.push_str(body);
+    }
+    m.push_str(&format!("--{}--\n", boundary));
+    m
+}
+
+/// Render a report with a user-provided Handlebars template.
+fn render_template(path: &str, json: &JsonReport) -> Result<String> {
+    let template = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read template {}: {}", path, e))?;
+    let mut hb = handlebars::Handlebars::new();
+    // Reports are usually Markdown or plain text, not HTML.
+    hb.register_escape_fn(handlebars::no_escape);
+    hb.render_template(&template, json)
+        .map_err(|e| anyhow::anyhow!("Failed to render template {}: {}", path, e))
+}
+
+/// A rendered report for one window, along with the parsed events that the
+/// other outputs (badges, exports, ...) are generated from.
+struct Report {
+    front_matter: String,
+    report: String,
+    events: RepoEventParseData,
+}
+
+/// Render the report for one window.
+fn render_report(opt: &Opt, w: &Window, events: RepoEventParseData) -> Result<Report> {
+    let user = opt.user.as_str();
+    let (start, end) = (&w.start, &w.end);
+    if opt.is_standalone() {
+        return Ok(Report {
+            front_matter: String::new(),
+            report: render_standalone(opt, w, &events)?,
+            events,
+        });
+    }
+    let mut front_matter = Vec::new();
+    match opt.front_matter {
+        Some(FrontMatter::Yaml) => {
+            print_front_matter(&mut front_matter, user, start, end, &events)?
+        }
+        Some(FrontMatter::Hugo) => print_post_front_matter(&mut front_matter, user, end, &events)?,
+        None => {}
+    }
+    let render_opts = RenderOptions::from_opt(opt);
+    let mut report = Vec::new();
+    if let Some(ref cmd) = opt.summarize_cmd {
+        let json = JsonReport {
+            user,
+            start,
+            end,
+            events: &events,
+        };
+        let summary = run_summarize_cmd(cmd, &json)?;
+        writeln!(report, "{}", summary.trim())?;
+        writeln!(report)?;
+    }
+    writeln!(report, "{}", window_header(start, end, opt.locale))?;
+    if let Some(t) = events.truncated_at {
+        writeln!(report, "{}", truncation_warning(&t))?;
+    }
+    if let Some(note) = absence_note(start.date_naive(), end.date_naive(), &opt.absence) {
+        writeln!(report, "{}", note)?;
+    }
+    report.extend_from_slice(render_to_string(&events, opt.format(), &render_opts)?.as_bytes());
+    Ok(Report {
+        front_matter: String::from_utf8(front_matter)?,
+        report: String::from_utf8(report)?,
+        events,
+    })
+}
+
+/// Open `path` for output (creating its directory), or stdout if unset.
+fn open_output(path: Option<&str>, append: bool) -> Result<Box<dyn Write>> {
+    Ok(if let Some(path) = path {
+        let path = std::path::Path::new(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let f = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        Box::new(std::io::BufWriter::new(f))
+    } else {
+        Box::new(std::io::stdout())
+    })
+}
+
+/// Write a report to the window's output, signing it if requested.
+fn write_report(opt: &Opt, w: &Window, r: &Report) -> Result<()> {
+    let mut out = open_output(w.output.as_deref(), opt.append)?;
+    let mut doc = r.front_matter.clone().into_bytes();
+    doc.extend_from_slice(r.report.as_bytes());
+    out.write_all(&doc)?;
+    if let Some(ref cmd) = opt.sign {
+        let sig = pipe_through(cmd, &doc)?;
+        if let Some(ref path) = w.output {
+            std::fs::write(format!("{}.sig", path), sig)?;
+        } else {
+            writeln!(out)?;
+            out.write_all(&sig)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn print_dry_run(opt: &Opt, windows: &[Window], period: &str) {
+    for w in windows {
+        println!("Window: {} to {}", w.start, w.end);
+        println!(
+            "Would write report to: {}",
+            w.output.as_deref().unwrap_or("stdout")
+        );
+    }
+    if let Some(ref f) = opt.from_file {
+        println!("Would read events from: {}", f);
+    } else {
+        println!("Would issue (stopping at the first page with no events in the window):");
+        for login in std::iter::once(&opt.user).chain(opt.also_login.iter()) {
+            for page in 1..=PAGE_LIMIT + 1 {
+                println!("  {}", describe_query(login, page));
+            }
+        }
+        if !opt.email.is_empty() {
+            for repo in opt.push_repo.iter() {
+                for page in 1..=PAGE_LIMIT {
+                    println!(
+                        "  GET https://api.github.com/repos/{}/events?page={}",
+                        repo, page
+                    );
+                }
+            }
+        }
+    }
+    // Queries are shown unencoded, as they contain placeholders.
+    let search = |q: &str| format!("GET https://api.github.com/search/issues?q={}", q);
+    if opt.search_backfill {
+        println!("If the event feed doesn't reach back to the window start, would issue:");
+        for w in windows {
+            let start = w.start.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%SZ");
+            let range = format!("{}..<end of feed>", start);
+            for q in [
+                format!("author:{} is:pr created:{}", opt.user, range),
+                format!("commenter:{} updated:{}", opt.user, range),
+            ] {
+                println!("  {}", search(&q));
+            }
+        }
+    }
+    if opt.resolve_renames && opt.from_file.is_none() {
+        println!("Would look up each repository with activity:");
+        println!("  GET https://api.github.com/repos/<owner>/<repo>");
+    }
+    if opt.detect_first_contributions {
+        println!(
+            "Would check each repository with activity not owned by {}:",
+            opt.user
+        );
+        for w in windows {
+            let before = w.start.with_timezone(&Utc).format("%Y-%m-%dT%H:%M:%SZ");
+            let q = format!(
+                "repo:<owner>/<repo> author:{} created:<{}",
+                opt.user, before
+            );
+            println!("  {}", search(&q));
+        }
+    }
+    if opt.check_resolved {
+        println!("Would check each issue only commented on:");
+        println!("  GET https://api.github.com/repos/<owner>/<repo>/issues/<n>/timeline?per_page=100&page=1");
+    }
+    if let Some(ref cmd) = opt.summarize_cmd {
+        println!("Would pipe the report as JSON to: {}", cmd);
+    }
+    if let Some(ref cmd) = opt.sign {
+        let to = match windows.last().and_then(|w| w.output.as_deref()) {
+            Some(path) => format!("{}.sig", path),
+            None => "stdout".to_string(),
+        };
+        println!("Would sign the report with: {} (signature to {})", cmd, to);
+    }
+    if let Some(ref dir) = opt.badge_dir {
+        println!("Would write badges to: {}", dir);
+    }
+    if let Some(ref dir) = opt.split_by_repo {
+        println!("Would write a file per repository to: {}", dir);
+    }
+    if opt.update_profile_readme {
+        println!(
+            "Would update: https://github.com/{0}/{0}/blob/HEAD/README.md",
+            opt.user
+        );
+    }
+    if let (Some(ref base), Some(last)) = (&opt.confluence_url, windows.last()) {
+        println!(
+            "Would publish to Confluence: {} space {} page {:?}",
+            base,
+            opt.confluence_space.as_deref().unwrap_or("(unset)"),
+            expand_output_template(&opt.confluence_title, &opt.user, &last.end, period)
+        );
+    }
+    for e in opt.export.iter() {
+        println!("Would export: {}", e);
+    }
+    if let Some(ref path) = opt.chart_file {
+        println!("Would write a chart to: {}", path);
+    }
+    if let Some(ref path) = opt.heatmap_file {
+        println!("Would write a heatmap to: {}", path);
+    }
+    if let Some(ref path) = opt.emit_meta {
+        println!("Would write run metadata to: {}", path);
+    }
+    if opt.notify {
+        println!("Would show a desktop notification");
+    }
+}
+
+/// Print a oneshot user service and a timer for it; the user is expected
+/// to save these into `~/.config/systemd/user/`.
+fn print_systemd_units(opt: &Opt) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    println!("# ~/.config/systemd/user/mygithubstatus.service");
+    println!("[Unit]");
+    println!("Description=Render GitHub activity report for {}", opt.user);
+    println!();
+    println!("[Service]");
+    println!("Type=oneshot");
+    println!("# Put GITHUB_TOKEN=... and other MYGHSTATUS_* settings here");
+    println!("EnvironmentFile=-%h/.config/mygithubstatus/env");
+    println!("Environment=MYGHSTATUS_USER={}", opt.user);
+    println!(
+        "Environment=MYGHSTATUS_DAY_START={}",
+        opt.day_start.format("%H:%M")
+    );
+    println!("ExecStart={}", exe.display());
+    println!();
+    println!("# ~/.config/systemd/user/mygithubstatus.timer");
+    println!("[Unit]");
+    println!("Description=Render GitHub activity report every weekday morning");
+    println!();
+    println!("[Timer]");
+    let at = opt.day_start + chrono::Duration::minutes(30);
+    println!("OnCalendar=Mon..Fri *-*-* {}", at.format("%H:%M:%S"));
+    println!("Persistent=true");
+    println!();
+    println!("[Install]");
+    println!("WantedBy=timers.target");
+    Ok(())
+}
+
+/// Process exit statuses, so wrapper scripts and monitoring can tell
+/// outcomes apart without parsing stderr.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    Success = 0,
+    Failure = 1,
+    /// The report was generated, but there was nothing to report.
+    NoActivity = 2,
+    AuthFailed = 3,
+    RateLimited = 4,
+    /// The report was generated, but may be missing activity.
+    Partial = 5,
+}
+
+/// The exit status for a failed run.
+fn error_status(e: &anyhow::Error) -> Status {
+    let (status, message) = if let Some(github_v3::GHError::Response { status, message }) =
+        e.downcast_ref::<github_v3::GHError>()
+    {
+        (status.as_u16(), message.clone().unwrap_or_default())
+    } else if let Some(e) = e.downcast_ref::<reqwest::Error>() {
+        match e.status() {
+            Some(status) => (status.as_u16(), e.to_string()),
+            None => return Status::Failure,
+        }
+    } else {
+        return Status::Failure;
+    };
+    match status {
+        429 => Status::RateLimited,
+        403 if message.to_lowercase().contains("rate limit") => Status::RateLimited,
+        401 | 403 => Status::AuthFailed,
+        _ => Status::Failure,
+    }
+}
+
+/// How a successful run went, judging by the activity in each window.
+fn report_status(windows: &[RepoEventParseData]) -> Status {
+    let partial = windows
+        .iter()
+        .any(|e| e.truncated_at.is_some() || e.repos.values().any(|r| r.unreachable()));
+    if partial {
+        Status::Partial
+    } else if windows
+        .iter()
+        .all(|e| e.repos.values().all(|r| r.activity() == 0))
+    {
+        Status::NoActivity
+    } else {
+        Status::Success
+    }
+}
+
+/// Run the command line tool, reporting any error on stderr.
+pub async fn main() -> std::process::ExitCode {
+    let status = match run().await {
+        Ok(status) => status,
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            error_status(&e)
+        }
+    };
+    std::process::ExitCode::from(status as u8)
+}
+
+async fn run() -> Result<Status> {
+    // Logs go to stderr; avoid escape sequences when that isn't a terminal
+    // (e.g. in a container or under cron).
+    simple_logger::SimpleLogger::new()
+        // As with SimpleLogger::from_env(), only errors unless RUST_LOG says otherwise.
+        .with_level(log::LevelFilter::Error)
+        .with_colors(std::io::stderr().is_terminal())
+        // Traces every template expression at debug level.
+        .with_module_level("handlebars", log::LevelFilter::Warn)
+        // Would draw over the --tui screen.
+        .with_module_level("mio", log::LevelFilter::Warn)
+        .env()
+        .init()
+        .unwrap();
+    let mut opt = Opt::from_args();
+    opt.apply_env_flags();
+    run_with(opt).await
+}
+
+async fn run_with(mut opt: Opt) -> Result<Status> {
+    let started = std::time::Instant::now();
+    if let Some(Command::Mcp) = opt.cmd {
+        mcp::serve(&opt).await?;
+        return Ok(Status::Success);
+    }
+    if let Some(Command::Cache(ref cmd)) = opt.cmd {
+        cache_command(&opt, cmd)?;
+        return Ok(Status::Success);
+    }
+    if let Some(Command::SystemdUnit) = opt.cmd {
+        print_systemd_units(&opt)?;
+        return Ok(Status::Success);
+    }
+    // A quarter's worth of items is too long to list.
+    opt.digest |= opt.quarter.is_some();
+    // Markdown is for pasting elsewhere; for reading it right here, style it.
+    if opt.format.is_none()
+        && opt.output.is_none()
+        && opt.template.is_none()
+        && !opt.update_profile_readme
+        && std::env::var_os("NO_COLOR").is_none()
+        && std::io::stdout().is_terminal()
+    {
+        opt.format = Some(Format::Terminal);
+    }
+    let user = opt.user.as_str();
+    let c = github_v3::Client::new_from_env();
+    let clock: Box<dyn Clock> = match (opt.now, opt.as_of) {
+        (Some(now), _) => Box::new(FixedClock(now.with_timezone(&Local))),
+        (None, Some(day)) => Box::new(FixedClock(day_start(day, opt.day_start))),
+        (None, None) => Box::new(SystemClock),
+    };
+    let (windows, period) = report_windows(&opt, clock.as_ref())?;
+    let windows: Vec<Window> = windows
+        .into_iter()
+        .map(|(start, end)| Window {
+            output: opt
+                .output
+                .as_ref()
+                .map(|o| expand_output_template(o, user, &end, period)),
+            start,
+            end,
+        })
+        .collect();
+    if opt.dry_run {
+        print_dry_run(&opt, &windows, period);
+        return Ok(Status::Success);
+    }
+    // Everything is fetched once, covering all windows.
+    let start = windows.iter().map(|w| w.start).min().expect("a window");
+    let end = windows.iter().map(|w| w.end).max().expect("a window");
+    let mut stats = FetchStats::default();
+    let mut timings = Timings::default();
+    // Statistics go where the (last) report would.
+    let stats_output = || open_output(windows.last().and_then(|w| w.output.as_deref()), opt.append);
+    if let Some(Command::Stats(StatsCommand::Received { ref repo })) = opt.cmd {
+        let logins = opt.logins();
+        let mut repos = repo.clone();
+        if repos.is_empty() {
+            if opt.from_file.is_none() {
+                repos.extend(owned_repos(&c, user).await?);
+            }
+            repos.extend(opt.maintain.iter().filter(|m| m.contains('/')).cloned());
+        }
+        let events: Vec<Event> = if let Some(ref f) = opt.from_file {
+            read_events_file(f)?
+        } else {
+            let mut r = Vec::new();
+            for repo in repos.iter() {
+                let events = repo_events(&c, repo, &start, &mut stats).await;
+                r.extend(unless_gone(events, repo)?);
+            }
+            r
+        };
+        let received = received_activity(&events, &repos, &logins, &start, &end);
+        let mut out = stats_output()?;
+        print_received(&mut out, &received)?;
+        out.flush()?;
+        return Ok(if received.is_empty() {
+            Status::NoActivity
+        } else {
+            Status::Success
+        });
+    }
+    if let Some(Command::Stats(StatsCommand::ResponseTime { ref repo })) = opt.cmd {
+        let logins = opt.logins();
+        let events: Vec<Event> = if let Some(ref f) = opt.from_file {
+            read_events_file(f)?
+                .into_iter()
+                .filter(|e| e.repo.name == *repo)
+                .collect()
+        } else {
+            unless_gone(repo_events(&c, repo, &start, &mut stats).await, repo)?
+        };
+        let times = response_times(&events, &logins, &start, &end);
+        let mut out = stats_output()?;
+        print_response_times(&mut out, &times)?;
+        out.flush()?;
+        return Ok(if times.is_empty() {
+            Status::NoActivity
+        } else {
+            Status::Success
+        });
+    }
+    let cache =
+        if opt.no_cache || opt.tui || opt.by_okr || matches!(opt.cmd, Some(Command::Stats(_))) {
+            None
+        } else {
+            cache::ReportCache::new(opt.cache_ttl)
+        };
+    let keys = windows
+        .iter()
+        .map(|w| report_cache_key(&opt, w))
+        .collect::<Result<Vec<_>>>()?;
+    let cached: Option<Vec<cache::CachedEvents>> = cache
+        .as_ref()
+        .and_then(|c| keys.iter().map(|k| c.load(k)).collect());
+    // On stderr, so the report itself is the same whether cached or not.
+    if let Some(created) = cached
+        .as_ref()
+        .and_then(|c| c.iter().map(|e| e.created).min())
+    {
+        eprintln!(
+            "Using activity cached {} ago; use --no-cache to refetch",
+            format_duration(Utc::now() - created)
+        );
+    }
+    let cache_hit = cached.is_some();
+    let (events_total, window_events) = if let Some(cached) = cached {
+        let total = cached.iter().map(|c| c.fetched).max().unwrap_or_default();
+        (total, cached.into_iter().map(|c| c.events).collect())
+    } else {
+        timings.start("fetch");
+        let raw_events: Vec<Box<Event>> = if let Some(ref f) = opt.from_file {
+            read_events_file(f)?.into_iter().map(Box::new).collect()
+        } else {
+            let mut r = my_events(&c, user, &start, &mut stats).await?;
+            for login in opt.also_login.iter() {
+                r.extend(my_events(&c, login, &start, &mut stats).await?);
+            }
+            r
+        };
+        let events_total = raw_events.len() as u32;
+        let raw_events = raw_events
+            .into_iter()
+            .filter(|e| label_filter_matches(e, &opt.label_filter, &opt.exclude_label))
+            .filter(|e| match opt.milestone {
+                Some(ref m) => e.milestone().map(|em| em.title == *m).unwrap_or(false),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        let mut pushes = Vec::new();
+        if !opt.email.is_empty() && opt.from_file.is_none() {
+            for repo in opt.push_repo.iter() {
+                let events = repo_events(&c, repo, &start, &mut stats).await;
+                pushes.push(unless_gone(events, repo)?);
+            }
+        }
+        timings.stop("fetch");
+        let logins = opt.logins();
+        let mut repo_names = HashMap::new();
+        let mut window_events = Vec::new();
+        for (w, key) in windows.iter().zip(keys.iter()) {
+            let (start, end) = (&w.start, &w.end);
+            timings.start("parse");
+            let mut events = parse_events(raw_events.iter().cloned(), start, end);
+            for repo_events in pushes.iter() {
+                attribute_pushes(&mut events, repo_events, &logins, &opt.email, start, end);
+            }
+            timings.stop("parse");
+            timings.start("enrich");
+            if let Some(t) = stats.feed_truncated_at.filter(|t| t > start) {
+                events.truncated_at = Some(t);
+                if opt.search_backfill {
+                    let until = t.min(end.with_timezone(&Utc));
+                    search_backfill(&c, user, start, &until, &mut events).await?;
+                }
+            }
+            if opt.resolve_renames && opt.from_file.is_none() {
+                resolve_renames(&c, &mut events, &mut repo_names).await?;
+            }
+            if opt.detect_first_contributions {
+                detect_first_contributions(&c, user, start, &mut events).await?;
+            }
+            if opt.check_resolved {
+                check_resolved(&c, end, &mut events).await?;
+            }
+            timings.stop("enrich");
+            if let Some(ref cache) = cache {
+                if let Err(e) = cache.store(key, events_total, &events) {
+                    log::warn!("Failed to cache activity: {}", e);
+                }
+            }
+            if opt.tui {
+                tui::select(&mut events)?;
+            }
+            window_events.push(events);
+        }
+        (events_total, window_events)
+    };
+    if let Some(Command::Brief) = opt.cmd {
+        for events in window_events.iter() {
+            println!("{}", brief(period, events));
+        }
+        return Ok(report_status(&window_events));
+    }
+    if let Some(Command::Stats(StatsCommand::Topics { top })) = opt.cmd {
+        let mut out = stats_output()?;
+        print_each_window(
+            &mut out,
+            &windows,
+            &window_events,
+            opt.locale,
+            |w, events| print_topics(w, events, top),
+        )?;
+        out.flush()?;
+        return Ok(report_status(&window_events));
+    }
+    if opt.by_okr {
+        let mut out = stats_output()?;
+        print_each_window(
+            &mut out,
+            &windows,
+            &window_events,
+            opt.locale,
+            |w, events| print_okrs(w, events, &opt.okr),
+        )?;
+        out.flush()?;
+        return Ok(report_status(&window_events));
+    }
+    let status = report_status(&window_events);
+    timings.start("render");
+    let reports = windows
+        .iter()
+        .zip(window_events)
+        .map(|(w, events)| render_report(&opt, w, events))
+        .collect::<Result<Vec<_>>>()?;
+    timings.stop("render");
+    timings.start("deliver");
+    for (i, (w, r)) in windows.iter().zip(reports.iter()).enumerate() {
+        if i > 0 && w.output.is_none() {
+            println!();
+        }
+        write_report(&opt, w, r)?;
+    }
+    // Other outputs describe the last window.
+    let (w, r) = (windows.last(), reports.last());
+    let (w, r) = w.zip(r).expect("a window");
+    let (start, end, events) = (w.start, w.end, &r.events);
+    if opt.update_profile_readme {
+        update_profile_readme(&c, user, &r.report).await?;
+    }
+    if let Some(ref base) = opt.confluence_url {
+        let space = opt
+            .confluence_space
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("--confluence-space is required"))?;
+        let title = expand_output_template(&opt.confluence_title, user, &end, period);
+        let mut body = Vec::new();
+        print_events_confluence(&mut body, events, &RenderOptions::from_opt(&opt))?;
+        publish_confluence(base, space, &title, std::str::from_utf8(&body)?).await?;
+    }
+    if let Some(ref dir) = opt.badge_dir {
+        write_badges(dir, events)?;
+    }
+    if let Some(ref dir) = opt.split_by_repo {
+        write_repo_files(dir, &opt, &start, &end, events)?;
+    }
+    for e in opt.export.iter() {
+        e.write(user, &start, &end, events)?;
+    }
+    if let Some(ref path) = opt.chart_file {
+        let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+        write_chart_svg(&mut f, events)?;
+        f.flush()?;
+    }
+    if let Some(ref path) = opt.heatmap_file {
+        let mut f = std::io::BufWriter::new(std::fs::File::create(path)?);
+        // Windows end at the start of a day, but that day's early hours
+        // are included; show it only if there was activity then.
+        let last = (end - chrono::Duration::days(1)).date_naive();
+        let last = events
+            .by_day
+            .keys()
+            .next_back()
+            .map_or(last, |d| last.max(*d));
+        write_heatmap_svg(&mut f, events, start.date_naive(), last)?;
+        f.flush()?;
+    }
+    timings.stop("deliver");
+    if opt.timings {
+        timings.print(&stats);
+    }
+    if let Some(ref path) = opt.emit_meta {
+        let meta = RunMeta {
+            user,
+            start,
+            end,
+            from_file: opt.from_file.as_deref(),
+            fetch: stats,
+            cache_hit,
+            events_total,
+            events_before: events.before,
+            events_after: events.after,
+            duration_secs: started.elapsed().as_secs_f64(),
+        };
+        let f = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer_pretty(f, &meta)?;
+    }
+    // Last, as notify-send waits for the notification to be clicked or
+    // dismissed when it has an action.
+    if opt.notify {
+        if let Err(e) = notify(&brief(period, events), w.output.as_deref()) {
+            log::warn!("Failed to show notification: {}", e);
+        }
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Options as parsed from `args`, after `--user alice`.
+    fn opt(args: &[&str]) -> Opt {
+        let argv = ["mygithubstatus", "--user", "alice"];
+        Opt::from_iter_safe(argv.iter().chain(args)).expect("valid arguments")
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().expect("valid date")
+    }
+
+    /// A day in 1900..2100; chrono handles far more, but the report code
+    /// adds days and months freely.
+    fn any_date() -> impl Strategy<Value = NaiveDate> {
+        (0i64..73_000).prop_map(|n| date("1900-01-01") + chrono::Duration::days(n))
+    }
+
+    const REPOS: [&str; 3] = ["alice/dotfiles", "coreos/rpm-ostree", "ostreedev/ostree"];
+    const TYPES: [&str; 6] = [
+        "PushEvent",
+        "IssueCommentEvent",
+        "IssuesEvent",
+        "PullRequestEvent",
+        "PullRequestReviewEvent",
+        "WatchEvent",
+    ];
+
+    /// An event of one of the `TYPES` on item `n` of one of the `REPOS`,
+    /// `minutes` after the Unix epoch.
+    fn event(typ: usize, repo: usize, n: u32, minutes: i64) -> Box<Event> {
+        let repo = REPOS[repo];
+        let issue = serde_json::json!({
+            "url": format!("https://api.github.com/repos/{}/issues/{}", repo, n),
+            "html_url": format!("https://github.com/{}/issues/{}", repo, n),
+            "title": format!("Issue {}", n),
+        });
+        let pr = serde_json::json!({
+            "url": format!("https://api.github.com/repos/{}/pulls/{}", repo, n),
+            "html_url": format!("https://github.com/{}/pull/{}", repo, n),
+            "title": format!("PR {}", n),
+        });
+        let payload = match TYPES[typ] {
+            "IssueCommentEvent" => serde_json::json!({
+                "issue": issue,
+                "comment": {"url": "", "html_url": "", "body": "Thanks"},
+            }),
+            "IssuesEvent" => serde_json::json!({
+                "action": if n.is_multiple_of(2) { "opened" } else { "closed" },
+                "issue": issue,
+            }),
+            "PullRequestEvent" => serde_json::json!({"action": "opened", "pull_request": pr}),
+            "PullRequestReviewEvent" => serde_json::json!({
+                "pull_request": pr,
+                "review": {
+                    "pull_request_url": "",
+                    "submitted_at": "2024-09-10T12:00:00Z",
+                    "state": "approved",
+                },
+            }),
+            _ => serde_json::json!({}),
+        };
+        let t = Utc.timestamp_opt(0, 0).unwrap() + chrono::Duration::minutes(minutes);
+        Box::new(
+            serde_json::from_value(serde_json::json!({
+                "id": "1",
+                "type": TYPES[typ],
+                "actor": {"id": 1, "login": "alice"},
+                "repo": {"id": 1, "name": repo, "url": ""},
+                "payload": payload,
+                "created_at": t,
+            }))
+            .expect("valid event"),
+        )
+    }
+
+    fn any_events() -> impl Strategy<Value = Vec<(usize, usize, u32, i64)>> {
+        proptest::collection::vec(
+            (0..TYPES.len(), 0..REPOS.len(), 1u32..6, 0i64..3 * 24 * 60),
+            0..40,
+        )
+    }
+
+    /// The middle day of the three the generated events span.
+    fn window() -> (DateTime<Local>, DateTime<Local>) {
+        let day = Utc.timestamp_opt(24 * 3600, 0).unwrap();
+        let start = day.with_timezone(&Local);
+        (start, start + chrono::Duration::days(1))
+    }
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/events.json");
+
+    fn fixture() -> Vec<serde_json::Value> {
+        serde_json::from_slice(&std::fs::read(FIXTURE).unwrap()).unwrap()
+    }
+
+    /// Field names of events and their payloads, so that generated objects
+    /// sometimes look like (parts of) events.
+    const KEYS: [&str; 16] = [
+        "id",
+        "type",
+        "actor",
+        "login",
+        "repo",
+        "name",
+        "payload",
+        "created_at",
+        "action",
+        "issue",
+        "pull_request",
+        "comment",
+        "review",
+        "title",
+        "html_url",
+        "url",
+    ];
+
+    fn any_json() -> impl Strategy<Value = serde_json::Value> {
+        let leaf = prop_oneof![
+            Just(serde_json::Value::Null),
+            any::<bool>().prop_map(Into::into),
+            any::<i64>().prop_map(Into::into),
+            any::<f64>().prop_map(Into::into),
+            ".*".prop_map(serde_json::Value::String),
+            Just(serde_json::json!("2026-10-14T12:00:00Z")),
+            proptest::sample::select(&TYPES[..]).prop_map(Into::into),
+        ];
+        leaf.prop_recursive(4, 64, 8, |inner| {
+            let key = prop_oneof![
+                proptest::sample::select(&KEYS[..]).prop_map(String::from),
+                ".*"
+            ];
+            prop_oneof![
+                proptest::collection::vec(inner.clone(), 0..8).prop_map(serde_json::Value::Array),
+                proptest::collection::btree_map(key, inner, 0..8)
+                    .prop_map(|m| serde_json::Value::Object(m.into_iter().collect())),
+            ]
+        })
+    }
+
+    /// JSON pointers to every value nested in `v`.
+    fn pointers(v: &serde_json::Value, prefix: String, out: &mut Vec<String>) {
+        let children: Vec<(String, &serde_json::Value)> = match v {
+            serde_json::Value::Object(m) => m.iter().map(|(k, v)| (k.clone(), v)).collect(),
+            serde_json::Value::Array(a) => a
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), v))
+                .collect(),
+            _ => return,
+        };
+        for (k, v) in children {
+            let p = format!("{}/{}", prefix, k);
+            pointers(v, p.clone(), out);
+            out.push(p);
+        }
+    }
+
+    /// Decode `values` and parse them over a window covering the fixture,
+    /// checking that every decoded event is accounted for at most once.
+    fn decode_and_parse(values: Vec<serde_json::Value>) -> std::result::Result<(), TestCaseError> {
+        let events: Vec<Box<Event>> = decode_events(values).into_iter().map(Box::new).collect();
+        let n = events.len() as u32;
+        let start = Utc
+            .with_ymd_and_hms(2026, 10, 13, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&Local);
+        let end = start + chrono::Duration::days(2);
+        let r = parse_events(events, &start, &end);
+        let in_window: u32 = r.by_day.values().sum();
+        prop_assert!(r.before + r.after + in_window <= n);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn parse_events_accounts_for_every_event(events in any_events()) {
+            let (start, end) = window();
+            let n = events.len() as u32;
+            let skipped = events.iter().filter(|e| TYPES[e.0] == "WatchEvent").count() as u32;
+            let events = events.into_iter().map(|(t, r, n, m)| event(t, r, n, m));
+            let r = parse_events(events, &start, &end);
+            let in_window: u32 = r.by_day.values().sum();
+            prop_assert!(r.before + r.after + in_window <= n);
+            prop_assert!(r.before + r.after + in_window + skipped >= n);
+        }
+
+        #[test]
+        fn parse_events_ignores_events_outside_the_window(events in any_events()) {
+            let (start, end) = window();
+            let outside: Vec<_> = events
+                .iter()
+                .filter(|e| !(24 * 60..=2 * 24 * 60).contains(&e.3))
+                .cloned()
+                .collect();
+            let r = parse_events(
+                outside.iter().map(|&(t, r, n, m)| event(t, r, n, m)),
+                &start,
+                &end,
+            );
+            prop_assert!(r.repos.values().all(|e| e.activity() == 0 && e.pushed == 0));
+            prop_assert_eq!((r.before + r.after) as usize, outside.len());
+        }
+
+        #[test]
+        fn parse_events_counts_pushes_and_comments(events in any_events()) {
+            let (start, end) = window();
+            let in_window = |m: &i64| (24 * 60..=2 * 24 * 60).contains(m);
+            let count = |typ: &str| {
+                events.iter().filter(|e| TYPES[e.0] == typ && in_window(&e.3)).count() as u32
+            };
+            let (pushes, comments) = (count("PushEvent"), count("IssueCommentEvent"));
+            let r = parse_events(
+                events.iter().map(|&(t, r, n, m)| event(t, r, n, m)),
+                &start,
+                &end,
+            );
+            let t = r.totals();
+            prop_assert_eq!(t.pushes, pushes);
+            // Issues and pull requests have different URLs here, so no
+            // comments are dropped as discussion on my own pull requests.
+            prop_assert_eq!(t.comments, comments);
+        }
+
+        #[test]
+        fn parse_events_lists_each_item_once(events in any_events()) {
+            let (start, end) = window();
+            let r = parse_events(
+                events.iter().map(|&(t, r, n, m)| event(t, r, n, m)),
+                &start,
+                &end,
+            );
+            for e in r.repos.values() {
+                for url in e.pr_action.keys() {
+                    prop_assert!(!e.reviewed.contains_key(url) && !e.issues.contains_key(url));
+                }
+                for url in e.reviewed.keys() {
+                    prop_assert!(!e.issues.contains_key(url));
+                }
+                for url in e.pr_action.keys().chain(e.reviewed.keys()).chain(e.issues.keys()) {
+                    prop_assert!(e.titles.contains_key(url) && e.times.contains_key(url));
+                }
+            }
+        }
+
+        #[test]
+        fn arbitrary_json_is_skipped_not_a_panic(values in proptest::collection::vec(any_json(), 0..8)) {
+            decode_and_parse(values)?;
+        }
+
+        #[test]
+        fn events_with_a_field_replaced_or_removed_parse(
+            i in any::<proptest::sample::Index>(),
+            j in any::<proptest::sample::Index>(),
+            replacement in proptest::option::of(any_json()),
+        ) {
+            let mut events = fixture();
+            let n = events.len();
+            let e = &mut events[i.index(n)];
+            let mut ps = Vec::new();
+            pointers(e, String::new(), &mut ps);
+            let p = &ps[j.index(ps.len())];
+            match replacement {
+                Some(v) => *e.pointer_mut(p).unwrap() = v,
+                None => {
+                    let (parent, key) = p.rsplit_once('/').unwrap();
+                    match e.pointer_mut(parent).unwrap() {
+                        serde_json::Value::Object(m) => {
+                            m.remove(key);
+                        }
+                        serde_json::Value::Array(a) => {
+                            a.remove(key.parse::<usize>().unwrap());
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            decode_and_parse(events)?;
+        }
+
+        #[test]
+        fn truncated_event_files_are_an_error(n in any::<proptest::sample::Index>()) {
+            let data = std::fs::read(FIXTURE).unwrap();
+            let n = n.index(data.len());
+            let path = std::env::temp_dir()
+                .join(format!("mygithubstatus-truncated-{}.json", std::process::id()));
+            std::fs::write(&path, &data[..n]).unwrap();
+            let r = read_events_file(path.to_str().unwrap());
+            std::fs::remove_file(&path).unwrap();
+            // Any proper prefix of the array is incomplete.
+            prop_assert!(r.is_err());
+        }
+
+        #[test]
+        fn truncated_event_lists_parse(n in any::<proptest::sample::Index>()) {
+            let mut events = fixture();
+            events.truncate(n.index(events.len() + 1));
+            decode_and_parse(events)?;
+        }
+
+        #[test]
+        fn date_range_roundtrips(a in any_date(), b in any_date()) {
+            let (first, last) = (a.min(b), a.max(b));
+            let r: DateRange = format!("{}..{}", first, last).parse().unwrap();
+            prop_assert_eq!((r.first, r.last), (first, last));
+            let r: DateRange = first.to_string().parse().unwrap();
+            prop_assert_eq!((r.first, r.last), (first, first));
+            prop_assert_eq!(
+                format!("{}..{}", last, first).parse::<DateRange>().is_ok(),
+                first == last
+            );
+        }
+
+        #[test]
+        fn iso_week_is_the_monday_of_the_week(d in any_date()) {
+            let w: IsoWeek = d.format("%G-W%V").to_string().parse().unwrap();
+            prop_assert_eq!(w.monday.weekday(), chrono::Weekday::Mon);
+            prop_assert!(w.monday <= d && d < w.monday + chrono::Duration::days(7));
+        }
+
+        #[test]
+        fn iso_week_rejects_invalid_weeks(year in 1900i32..2100, week in 54u32..100) {
+            let s = format!("{}-W{}", year, week);
+            prop_assert!(s.parse::<IsoWeek>().is_err());
+            let s = format!("{}-W0", year);
+            prop_assert!(s.parse::<IsoWeek>().is_err());
+        }
+
+        #[test]
+        fn month_covers_the_month(d in any_date()) {
+            let m: Month = d.format("%Y-%m").to_string().parse().unwrap();
+            prop_assert_eq!(m.first.day(), 1);
+            prop_assert!(m.first <= d && d < m.next());
+            prop_assert_eq!(m.next().day(), 1);
+        }
+
+        #[test]
+        fn month_rejects_invalid_months(year in 1900i32..2100, month in 13u32..100) {
+            let s = format!("{}-{}", year, month);
+            prop_assert!(s.parse::<Month>().is_err());
+            let s = format!("{}-00", year);
+            prop_assert!(s.parse::<Month>().is_err());
+        }
+
+        #[test]
+        fn quarter_covers_the_quarter(d in any_date()) {
+            let q = d.month0() / 3 + 1;
+            let a: Quarter = format!("Q{}-{}", q, d.year()).parse().unwrap();
+            let b: Quarter = format!("{}-Q{}", d.year(), q).parse().unwrap();
+            prop_assert_eq!(a.first, b.first);
+            prop_assert!(a.first <= d && d < a.next());
+            prop_assert_eq!(a.first.day(), 1);
+            prop_assert_eq!(a.first.month0() % 3, 0);
+        }
+
+        #[test]
+        fn quarter_rejects_invalid_quarters(year in 1900i32..2100, q in 5u32..100) {
+            let s = format!("Q{}-{}", q, year);
+            prop_assert!(s.parse::<Quarter>().is_err());
+            let s = format!("Q0-{}", year);
+            prop_assert!(s.parse::<Quarter>().is_err());
+        }
+
+        #[test]
+        fn report_windows_follow_window(a in any_date(), b in any_date()) {
+            let (first, last) = (a.min(b), a.max(b));
+            let o = opt(&["--window", &format!("{}..{}", first, last)]);
+            let (windows, period) = report_windows(&o, &SystemClock).unwrap();
+            prop_assert_eq!(period, "window");
+            let at = o.day_start;
+            prop_assert_eq!(
+                windows,
+                vec![(day_start(first, at), day_start(last.succ_opt().unwrap(), at))]
+            );
+        }
+
+        #[test]
+        fn report_windows_default_to_the_previous_workday(today in any_date()) {
+            let o = opt(&[]);
+            let clock = FixedClock(day_start(today, o.day_start));
+            let (windows, period) = report_windows(&o, &clock).unwrap();
+            prop_assert_eq!(period, "day");
+            let [(start, end)] = windows.as_slice() else {
+                panic!("expected one window");
+            };
+            prop_assert_eq!(*end, day_start(today, o.day_start));
+            let first = start.date_naive();
+            prop_assert!(is_workday(first, &[]));
+            // Only the weekend, if anything, lies between it and today.
+            prop_assert!(first < today);
+            prop_assert!(first
+                .iter_days()
+                .skip(1)
+                .take_while(|d| *d < today)
+                .all(|d| !is_workday(d, &[])));
+        }
+
+        #[test]
+        fn report_windows_cover_whole_periods(d in any_date()) {
+            let at = opt(&[]).day_start;
+            let week = d.format("%G-W%V").to_string();
+            let (w, _) = report_windows(&opt(&["--week", &week]), &SystemClock).unwrap();
+            // Not necessarily 7 × 24 hours, across a DST change.
+            let days = w[0].1.date_naive() - w[0].0.date_naive();
+            prop_assert_eq!(days, chrono::Duration::days(7));
+            prop_assert_eq!(w[0].0.weekday(), chrono::Weekday::Mon);
+            let month = d.format("%Y-%m").to_string();
+            let (w, _) = report_windows(&opt(&["--month", &month]), &SystemClock).unwrap();
+            prop_assert!(w[0].0 <= day_start(d, at) && day_start(d, at) < w[0].1);
+            let quarter = format!("Q{}-{}", d.month0() / 3 + 1, d.year());
+            let (w, _) = report_windows(&opt(&["--quarter", &quarter]), &SystemClock).unwrap();
+            prop_assert!(w[0].0 <= day_start(d, at) && day_start(d, at) < w[0].1);
+        }
+    }
+
+    /// A clock stopped at `time` (`HH:MM`) local time on `day`.
+    fn clock(day: &str, time: &str) -> FixedClock {
+        let t = date(day).and_time(parse_day_start(time).unwrap());
+        FixedClock(Local.from_local_datetime(&t).earliest().unwrap())
+    }
+
+    /// The single window `report_windows` gives for `args` at `clock`.
+    fn window_at(args: &[&str], clock: &FixedClock) -> (NaiveDate, NaiveDate, &'static str) {
+        let (windows, period) = report_windows(&opt(args), clock).unwrap();
+        assert_eq!(windows.len(), 1);
+        let (start, end) = windows[0];
+        (start.date_naive(), end.date_naive(), period)
+    }
+
+    #[test]
+    fn previous_workday_skips_weekends() {
+        // Tuesday, Monday
+        assert_eq!(
+            previous_workday(date("2024-09-10"), &[]),
+            date("2024-09-09")
+        );
+        assert_eq!(
+            previous_workday(date("2024-09-09"), &[]),
+            date("2024-09-06")
+        );
+        // Sunday, Saturday
+        assert_eq!(
+            previous_workday(date("2024-09-08"), &[]),
+            date("2024-09-06")
+        );
+        assert_eq!(
+            previous_workday(date("2024-09-07"), &[]),
+            date("2024-09-06")
+        );
+    }
+
+    #[test]
+    fn previous_workday_skips_absences() {
+        let absent: Vec<DateRange> = vec!["2024-09-05..2024-09-06".parse().unwrap()];
+        assert_eq!(
+            previous_workday(date("2024-09-09"), &absent),
+            date("2024-09-04")
+        );
+        assert_eq!(
+            previous_workday(date("2024-09-05"), &absent),
+            date("2024-09-04")
+        );
+        // Absences across a weekend
+        let absent: Vec<DateRange> = vec!["2024-09-06..2024-09-09".parse().unwrap()];
+        assert_eq!(
+            previous_workday(date("2024-09-10"), &absent),
+            date("2024-09-05")
+        );
+    }
+
+    #[test]
+    fn report_windows_look_back_over_the_weekend() {
+        let at = opt(&[]).day_start;
+        let monday = clock("2024-09-09", "10:00");
+        let (windows, period) = report_windows(&opt(&[]), &monday).unwrap();
+        assert_eq!(period, "day");
+        assert_eq!(
+            windows,
+            vec![(
+                day_start(date("2024-09-06"), at),
+                day_start(date("2024-09-09"), at)
+            )]
+        );
+        let tuesday = clock("2024-09-10", "10:00");
+        assert_eq!(
+            window_at(&[], &tuesday),
+            (date("2024-09-09"), date("2024-09-10"), "day")
+        );
+    }
+
+    #[test]
+    fn report_windows_use_day_start() {
+        let clock = clock("2024-09-10", "10:00");
+        let (windows, _) = report_windows(&opt(&["--day-start", "09:30"]), &clock).unwrap();
+        let (start, end) = windows[0];
+        assert_eq!(start.time(), parse_day_start("09:30").unwrap());
+        assert_eq!(end.time(), parse_day_start("09:30").unwrap());
+    }
+
+    #[test]
+    fn report_windows_skip_absences_and_previous_days() {
+        let monday = clock("2024-09-09", "10:00");
+        assert_eq!(
+            window_at(&["--absence", "2024-09-06"], &monday),
+            (date("2024-09-05"), date("2024-09-09"), "day")
+        );
+        // What Monday's run reported
+        let tuesday = clock("2024-09-10", "10:00");
+        assert_eq!(
+            window_at(&["--previous-day", "1"], &tuesday),
+            (date("2024-09-06"), date("2024-09-09"), "day")
+        );
+    }
+
+    #[test]
+    fn report_windows_count_the_last_week_back_from_today() {
+        let clock = clock("2024-09-11", "10:00");
+        assert_eq!(
+            window_at(&["--week"], &clock),
+            (date("2024-09-04"), date("2024-09-11"), "week")
+        );
+    }
+
+    #[test]
+    fn report_windows_run_since_until_now() {
+        let clock = clock("2024-09-11", "10:00");
+        let (windows, period) = report_windows(&opt(&["--since", "2024-09-01"]), &clock).unwrap();
+        assert_eq!(period, "window");
+        assert_eq!(windows[0].1, clock.now());
+        assert_eq!(windows[0].0.date_naive(), date("2024-09-01"));
+        let r = report_windows(&opt(&["--since", "2024-09-12"]), &clock);
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn report_windows_find_the_current_sprint() {
+        let args = ["--sprint", "current", "--sprint-start", "2024-09-02"];
+        assert_eq!(
+            window_at(&args, &clock("2024-09-11", "10:00")),
+            (date("2024-09-02"), date("2024-09-16"), "sprint")
+        );
+        assert_eq!(
+            window_at(&args, &clock("2024-09-16", "10:00")),
+            (date("2024-09-16"), date("2024-09-30"), "sprint")
+        );
+    }
+
+    #[test]
+    fn sprint_window_numbers_sprints_from_the_start() {
+        let at = opt(&[]).day_start;
+        let first = date("2024-09-02");
+        let today = date("2024-09-20");
+        let days = |(start, end): Span| (start.date_naive(), end.date_naive());
+        let sprint = |s, length| sprint_window(s, first, length, today, at).map(days);
+        assert_eq!(
+            sprint(Sprint::Current, 14).unwrap(),
+            (date("2024-09-16"), date("2024-09-30"))
+        );
+        assert_eq!(
+            sprint(Sprint::Previous, 14).unwrap(),
+            (date("2024-09-02"), date("2024-09-16"))
+        );
+        assert_eq!(
+            sprint(Sprint::Number(3), 7).unwrap(),
+            (date("2024-09-16"), date("2024-09-23"))
+        );
+        // Before sprint 1, the current sprint is sprint 0.
+        let r = sprint_window(Sprint::Current, first, 14, date("2024-08-30"), at).map(days);
+        assert_eq!(r.unwrap(), (date("2024-08-19"), date("2024-09-02")));
+    }
+
+    #[test]
+    fn sprint_window_rejects_bad_sprints() {
+        let at = opt(&[]).day_start;
+        let (first, today) = (date("2024-09-02"), date("2024-09-20"));
+        assert!(sprint_window(Sprint::Current, first, 0, today, at).is_err());
+        assert!(sprint_window(Sprint::Number(u32::MAX), first, u32::MAX, today, at).is_err());
+        assert!(sprint_window(Sprint::Number(1), first, u32::MAX, today, at).is_err());
+    }
+
+    #[test]
+    fn merge_keeps_resolved_issues() {
+        let url = "https://github.com/coreos/rpm-ostree/issues/9".to_string();
+        let commented = |comments, resolved| {
+            let mut e = RepoEvents::default();
+            let activity = IssueActivity {
+                state: None,
+                comments,
+                resolved,
+            };
+            e.issues.insert(url.clone(), activity);
+            e
+        };
+        let mut fresh = commented(1, false);
+        fresh.merge(commented(2, true));
+        assert_eq!(fresh.issues[&url].comments, 3);
+        assert!(fresh.issues[&url].resolved);
+        let mut resolved = commented(1, true);
+        resolved.merge(commented(2, false));
+        assert!(resolved.issues[&url].resolved);
+    }
+
+    #[test]
+    fn median_averages_the_middle_two() {
+        let minutes = |v: &[i64]| v.iter().map(|m| chrono::Duration::minutes(*m)).collect();
+        assert_eq!(median(Vec::new()), None);
+        assert_eq!(median(minutes(&[30])), Some(chrono::Duration::minutes(30)));
+        assert_eq!(
+            median(minutes(&[90, 10, 30])),
+            Some(chrono::Duration::minutes(30))
+        );
+        assert_eq!(
+            median(minutes(&[90, 10, 30, 60])),
+            Some(chrono::Duration::minutes(45))
+        );
+    }
+}
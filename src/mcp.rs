@@ -0,0 +1,370 @@
+//! A Model Context Protocol server over stdio (`mygithubstatus mcp`),
+//! exposing reports as tools for assistants and editor agents.
+//!
+//! Messages are newline-delimited JSON-RPC 2.0. Each tool call runs the
+//! normal report pipeline with the server's options, so configuration and
+//! the report cache are shared with regular runs.
+//!
+//! SIGTERM (or Ctrl-C) stops the server once any request in flight has
+//! been answered, so a supervisor stopping it never cuts off a response.
+
+use crate::{Command, DateRange, Format, Opt, RepoEventParseData, StatsCommand};
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+fn tools() -> Value {
+    let window = json!({
+        "type": "string",
+        "description": "Days to cover, `YYYY-MM-DD` or `YYYY-MM-DD..YYYY-MM-DD`; defaults to the last workday"
+    });
+    json!([
+        {
+            "name": "report",
+            "description": "My GitHub activity (pull requests, reviews, issues, pushes) over a range of days",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "window": window,
+                    "format": {
+                        "type": "string",
+                        "description": "Output format, e.g. `markdown`, `text`, `json` or `csv`; defaults to `markdown`"
+                    }
+                }
+            }
+        },
+        {
+            "name": "brief",
+            "description": "A one-line count of my GitHub activity over a range of days",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "window": window }
+            }
+        },
+        {
+            "name": "stats_topics",
+            "description": "The most frequent terms in my pull request and issue titles, per repository, over a range of days",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "window": window,
+                    "top": {
+                        "type": "integer",
+                        "description": "Number of terms to show per repository; defaults to 5"
+                    }
+                }
+            }
+        },
+        {
+            "name": "stats_received",
+            "description": "Activity by others on my repositories (pull requests, issues, contributors) over a range of days",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "window": window,
+                    "repo": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Repositories to check (`org/repo`); defaults to my own and those I maintain"
+                    }
+                }
+            }
+        },
+        {
+            "name": "stats_response_time",
+            "description": "Median time from an issue or pull request being opened in a repository to my first response",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "window": window,
+                    "repo": {
+                        "type": "string",
+                        "description": "Repository to check (`org/repo`)"
+                    }
+                },
+                "required": ["repo"]
+            }
+        }
+    ])
+}
+
+/// The options for one tool call: those of the server that shape a report,
+/// with everything that would publish, notify or write elsewhere left off.
+///
+/// Every field is listed, so a new option has to be classified here.
+fn tool_opt(
+    base: &Opt,
+    args: &Value,
+    format: Format,
+    cmd: Option<Command>,
+    output: &str,
+) -> Result<Opt> {
+    let window = match args.get("window").and_then(|w| w.as_str()) {
+        Some(window) => Some(
+            window
+                .parse::<DateRange>()
+                .map_err(|e| anyhow::anyhow!("Invalid window {}: {}", window, e))?,
+        ),
+        None => None,
+    };
+    let b = base.clone();
+    // An explicit window replaces however the server picks its window.
+    let (window, week, month, quarter, since, until) = match window {
+        Some(w) => (vec![w], None, None, None, None, None),
+        None => (b.window, b.week, b.month, b.quarter, b.since, b.until),
+    };
+    Ok(Opt {
+        // What to report on
+        previous_day: b.previous_day,
+        user: b.user,
+        also_login: b.also_login,
+        email: b.email,
+        push_repo: b.push_repo,
+        from_file: b.from_file,
+        label_filter: b.label_filter,
+        exclude_label: b.exclude_label,
+        day_start: b.day_start,
+        now: b.now,
+        as_of: b.as_of,
+        sprint_start: b.sprint_start,
+        sprint_length: b.sprint_length,
+        sprint: b.sprint,
+        absence: b.absence,
+        window,
+        since,
+        until,
+        week,
+        month,
+        quarter,
+        milestone: b.milestone,
+        maintain: b.maintain,
+        // How it is gathered and rendered
+        cache_ttl: b.cache_ttl,
+        no_cache: b.no_cache,
+        format: Some(format),
+        locale: b.locale,
+        search_backfill: b.search_backfill,
+        show_review_bodies: b.show_review_bodies,
+        show_comment_excerpts: b.show_comment_excerpts,
+        resolve_renames: b.resolve_renames,
+        no_emoji: b.no_emoji,
+        no_emoji_html: b.no_emoji_html,
+        html_theme: b.html_theme,
+        html_css: b.html_css,
+        front_matter: b.front_matter,
+        digest: b.digest,
+        okr: b.okr,
+        max_items: b.max_items,
+        collapsible: b.collapsible,
+        collapsible_categories: b.collapsible_categories,
+        collapsible_top: b.collapsible_top,
+        split_ownership: b.split_ownership,
+        detect_first_contributions: b.detect_first_contributions,
+        check_resolved: b.check_resolved,
+        summarize_cmd: b.summarize_cmd,
+        email_from: b.email_from,
+        email_to: b.email_to,
+        // The tool's own output
+        output: Some(output.to_string()),
+        append: false,
+        template: None,
+        // The tool's own mode, then other modes and side outputs
+        cmd,
+        dry_run: false,
+        emit_meta: None,
+        tui: false,
+        notify: false,
+        by_okr: false,
+        badge_dir: None,
+        split_by_repo: None,
+        update_profile_readme: false,
+        sign: None,
+        chart_file: None,
+        heatmap_file: None,
+        confluence_url: None,
+        confluence_space: None,
+        confluence_title: b.confluence_title,
+        export: Vec::new(),
+        timings: false,
+    })
+}
+
+/// The statistics subcommand a `stats_*` tool runs, if `name` is one.
+fn stats_command(name: &str, args: &Value) -> Result<Option<StatsCommand>> {
+    let repos = |v: Option<&Value>| -> Result<Vec<String>> {
+        match v {
+            Some(v) => Ok(serde_json::from_value(v.clone())?),
+            None => Ok(Vec::new()),
+        }
+    };
+    Ok(Some(match name {
+        "stats_topics" => StatsCommand::Topics {
+            top: match args.get("top") {
+                Some(top) => top
+                    .as_u64()
+                    .ok_or_else(|| anyhow::anyhow!("Invalid top: {}", top))?
+                    as usize,
+                None => 5,
+            },
+        },
+        "stats_received" => StatsCommand::Received {
+            repo: repos(args.get("repo"))?,
+        },
+        "stats_response_time" => StatsCommand::ResponseTime {
+            repo: args
+                .get("repo")
+                .and_then(|r| r.as_str())
+                .ok_or_else(|| anyhow::anyhow!("stats_response_time needs a repo"))?
+                .to_string(),
+        },
+        _ => return Ok(None),
+    }))
+}
+
+async fn call_tool(base: &Opt, name: &str, args: &Value) -> Result<String> {
+    let stats = stats_command(name, args)?;
+    let format = match (name, args.get("format").and_then(|f| f.as_str())) {
+        ("report", Some(f)) => f.parse()?,
+        ("report", None) => Format::Markdown,
+        ("brief", _) => Format::Json,
+        // Statistics are plain text whatever the format
+        _ if stats.is_some() => Format::Text,
+        _ => anyhow::bail!("Unknown tool: {}", name),
+    };
+    let output = std::env::temp_dir().join(format!("mygithubstatus-mcp-{}", std::process::id()));
+    let output = output.to_string_lossy();
+    let opt = tool_opt(base, args, format, stats.map(Command::Stats), &output)?;
+    // Boxed, as this is itself called from run_with().
+    let r = Box::pin(crate::run_with(opt)).await;
+    let text = std::fs::read_to_string(&*output);
+    let _ = std::fs::remove_file(&*output);
+    r?;
+    let text = text?;
+    if name == "brief" {
+        let events: RepoEventParseData = serde_json::from_str(&text)?;
+        return Ok(crate::brief_counts(&events));
+    }
+    if text.is_empty() {
+        return Ok("No activity in this window".to_string());
+    }
+    Ok(text)
+}
+
+async fn handle(
+    opt: &Opt,
+    method: &str,
+    params: &Value,
+) -> std::result::Result<Value, (i64, String)> {
+    match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": "mygithubstatus",
+                "version": env!("CARGO_PKG_VERSION")
+            }
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => {
+            let name = params.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let args = params
+                .get("arguments")
+                .cloned()
+                .unwrap_or_else(|| json!({}));
+            // Tool failures are reported to the model rather than as protocol errors.
+            let (text, is_error) = match call_tool(opt, name, &args).await {
+                Ok(text) => (text, false),
+                Err(e) => (e.to_string(), true),
+            };
+            Ok(json!({
+                "content": [{ "type": "text", "text": text }],
+                "isError": is_error
+            }))
+        }
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    }
+}
+
+/// Resolves once we've been asked to shut down.
+async fn shutdown_signal() -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut term = signal(SignalKind::terminate())?;
+        tokio::select! {
+            _ = term.recv() => Ok(()),
+            r = tokio::signal::ctrl_c() => r,
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await
+}
+
+/// Serve requests from stdin until it is closed or we're asked to shut down.
+pub(crate) async fn serve(opt: &Opt) -> Result<()> {
+    // Read on a thread of its own, which unlike a runtime task doesn't
+    // keep the process alive once we stop.
+    let (tx, mut lines) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let mut stdout = std::io::stdout();
+    // Only checked between requests, so one in flight is still answered.
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        let line = tokio::select! {
+            line = lines.recv() => match line {
+                Some(line) => line?,
+                None => break,
+            },
+            r = &mut shutdown => {
+                r?;
+                log::info!("Shutting down");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let msg: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(e) => {
+                let err = json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": -32700, "message": e.to_string() }
+                });
+                writeln!(stdout, "{}", err)?;
+                stdout.flush()?;
+                continue;
+            }
+        };
+        let method = msg.get("method").and_then(|m| m.as_str()).unwrap_or("");
+        let params = msg.get("params").cloned().unwrap_or(Value::Null);
+        let r = handle(opt, method, &params).await;
+        // Notifications (no id) get no response.
+        let id = match msg.get("id") {
+            Some(id) => id.clone(),
+            None => continue,
+        };
+        let response = match r {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message }
+            }),
+        };
+        writeln!(stdout, "{}", response)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
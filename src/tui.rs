@@ -0,0 +1,267 @@
+//! Interactive review of a report's items before it is rendered (`--tui`).
+
+use crate::{RepoEventParseData, RepoEvents};
+use anyhow::Result;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::crossterm::{execute, tty::IsTty};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::widgets::{Block, List, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+
+/// The UI is drawn on stderr, leaving stdout for the report itself
+/// (e.g. `--tui > report.md`).
+type Tty = Terminal<CrosstermBackend<std::io::Stderr>>;
+
+/// What an entry refers to within its repository.
+enum Target {
+    Url(String),
+    Pushes,
+    PushedBy(String),
+}
+
+struct Entry {
+    target: Target,
+    label: String,
+    included: bool,
+}
+
+struct Repo {
+    name: String,
+    entries: Vec<Entry>,
+}
+
+struct App {
+    repos: Vec<Repo>,
+    sidebar: ListState,
+    items: ListState,
+    focus_items: bool,
+}
+
+impl App {
+    fn new(events: &RepoEventParseData) -> Self {
+        let mut repos = Vec::new();
+        for (name, events) in events.repos.iter() {
+            let title = |url: &String| events.titles.get(url).cloned().unwrap_or_default();
+            let mut entries = Vec::new();
+            let mut push = |target, label: String| {
+                entries.push(Entry {
+                    target,
+                    label,
+                    included: true,
+                })
+            };
+            for url in events.pr_action.keys() {
+                push(Target::Url(url.clone()), format!("PR: {}", title(url)));
+            }
+            for url in events.reviewed.keys() {
+                push(
+                    Target::Url(url.clone()),
+                    format!("Reviewed: {}", title(url)),
+                );
+            }
+            for (url, activity) in events.issues.iter() {
+                let label = format!("Issue {}: {}", activity.describe(), title(url));
+                push(Target::Url(url.clone()), label);
+            }
+            if events.pushed > 0 {
+                push(Target::Pushes, format!("Pushed {} times", events.pushed));
+            }
+            for (pusher, n) in events.pushed_by_others.iter() {
+                let label = format!(
+                    "Authored {}, pushed by {}",
+                    crate::plural(*n as usize, "commit"),
+                    pusher
+                );
+                push(Target::PushedBy(pusher.clone()), label);
+            }
+            if !entries.is_empty() {
+                repos.push(Repo {
+                    name: name.clone(),
+                    entries,
+                });
+            }
+        }
+        Self {
+            repos,
+            sidebar: ListState::default().with_selected(Some(0)),
+            items: ListState::default().with_selected(Some(0)),
+            focus_items: false,
+        }
+    }
+
+    fn repo(&self) -> &Repo {
+        &self.repos[self.sidebar.selected().unwrap_or(0)]
+    }
+
+    fn step(&mut self, delta: isize) {
+        let (state, len) = if self.focus_items {
+            let len = self.repo().entries.len();
+            (&mut self.items, len)
+        } else {
+            (&mut self.sidebar, self.repos.len())
+        };
+        let i = state.selected().unwrap_or(0) as isize + delta;
+        state.select(Some(i.clamp(0, len as isize - 1) as usize));
+        if !self.focus_items {
+            self.items.select(Some(0));
+        }
+    }
+
+    fn toggle(&mut self) {
+        if !self.focus_items {
+            return;
+        }
+        let (r, i) = (self.sidebar.selected(), self.items.selected());
+        if let Some(e) = self.repos[r.unwrap_or(0)].entries.get_mut(i.unwrap_or(0)) {
+            e.included = !e.included;
+        }
+    }
+
+    /// Open the selected item (or for pushes, the repository) in a browser.
+    fn open(&self) -> Result<()> {
+        let repo = self.repo();
+        let url = match repo.entries.get(self.items.selected().unwrap_or(0)) {
+            Some(Entry {
+                target: Target::Url(url),
+                ..
+            }) if self.focus_items => url.clone(),
+            _ => format!("https://github.com/{}", repo.name),
+        };
+        crate::desktop_open(&url)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, items] =
+            Layout::horizontal([Constraint::Length(32), Constraint::Min(0)]).areas(main);
+        let border = |focused: bool| {
+            if focused {
+                Style::new().bold()
+            } else {
+                Style::new().dim()
+            }
+        };
+        let repos = List::new(self.repos.iter().map(|r| {
+            let n = r.entries.iter().filter(|e| e.included).count();
+            format!("{} ({}/{})", r.name, n, r.entries.len())
+        }))
+        .block(
+            Block::bordered()
+                .title("Repositories")
+                .border_style(border(!self.focus_items)),
+        )
+        .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(repos, sidebar, &mut self.sidebar);
+        let repo = &self.repos[self.sidebar.selected().unwrap_or(0)];
+        let entries = List::new(repo.entries.iter().map(|e| {
+            let mark = if e.included { "[x]" } else { "[ ]" };
+            format!("{} {}", mark, e.label)
+        }))
+        .block(
+            Block::bordered()
+                .title(repo.name.as_str())
+                .border_style(border(self.focus_items)),
+        )
+        .highlight_style(Style::new().reversed());
+        frame.render_stateful_widget(entries, items, &mut self.items);
+        frame.render_widget(
+            Paragraph::new(
+                "↑/↓ move  ←/→ switch pane  space include/exclude  o open  enter done  esc cancel",
+            )
+            .dim(),
+            help,
+        );
+    }
+
+    /// Drop the excluded items from the report.
+    fn apply(self, events: &mut RepoEventParseData) {
+        for repo in self.repos {
+            let Some(e) = events.repos.get_mut(&repo.name) else {
+                continue;
+            };
+            for entry in repo.entries.iter().filter(|e| !e.included) {
+                exclude(e, &entry.target);
+            }
+            if e.activity() == 0 {
+                events.repos.remove(&repo.name);
+            }
+        }
+    }
+}
+
+fn exclude(events: &mut RepoEvents, target: &Target) {
+    match target {
+        Target::Url(url) => {
+            events.pr_action.remove(url);
+            events.reviewed.remove(url);
+            events.review_bodies.remove(url);
+            events.issues.remove(url);
+            events.comment_bodies.remove(url);
+            events.labels.remove(url);
+            events.titles.remove(url);
+            events.times.remove(url);
+        }
+        Target::Pushes => {
+            events.pushed = 0;
+            events.last_push = None;
+        }
+        Target::PushedBy(pusher) => {
+            events.pushed_by_others.remove(pusher);
+        }
+    }
+}
+
+fn init() -> Result<Tty> {
+    terminal::enable_raw_mode()?;
+    execute!(std::io::stderr(), EnterAlternateScreen)?;
+    Ok(Terminal::new(CrosstermBackend::new(std::io::stderr()))?)
+}
+
+fn restore() {
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(std::io::stderr(), LeaveAlternateScreen);
+}
+
+fn run(terminal: &mut Tty, app: &mut App) -> Result<bool> {
+    loop {
+        terminal.draw(|f| app.draw(f))?;
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('q') => return Ok(true),
+            KeyCode::Esc => return Ok(false),
+            KeyCode::Tab => app.focus_items = !app.focus_items,
+            KeyCode::Left | KeyCode::Char('h') => app.focus_items = false,
+            KeyCode::Right | KeyCode::Char('l') => app.focus_items = true,
+            KeyCode::Down | KeyCode::Char('j') => app.step(1),
+            KeyCode::Up | KeyCode::Char('k') => app.step(-1),
+            KeyCode::Char(' ') => app.toggle(),
+            KeyCode::Char('o') => app.open()?,
+            _ => {}
+        }
+    }
+}
+
+/// Let the user browse the report and pick which items stay in it.
+pub(crate) fn select(events: &mut RepoEventParseData) -> Result<()> {
+    let mut app = App::new(events);
+    if app.repos.is_empty() {
+        return Ok(());
+    }
+    if !std::io::stderr().is_tty() {
+        anyhow::bail!("--tui needs a terminal on stderr");
+    }
+    let r = init().and_then(|mut terminal| run(&mut terminal, &mut app));
+    restore();
+    if !r? {
+        anyhow::bail!("Cancelled");
+    }
+    app.apply(events);
+    Ok(())
+}
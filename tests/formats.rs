@@ -0,0 +1,89 @@
+//! Snapshots of the report for `fixtures/events.json` in every `--format`.
+//!
+//! Run `cargo insta review` (or set `INSTA_UPDATE=always`) after an
+//! intended change to the output.
+
+use std::process::Command;
+
+fn report(format: &str, args: &[&str]) -> String {
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/events.json");
+    let out = Command::new(env!("CARGO_BIN_EXE_mygithubstatus"))
+        .env_clear()
+        .env("TZ", "UTC")
+        .args(["--user", "alice", "--no-cache", "--from-file", fixture])
+        .args(["--window", "2026-10-12..2026-10-14", "--format", format])
+        .args(args)
+        .output()
+        .expect("running mygithubstatus");
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8(out.stdout).expect("UTF-8 output")
+}
+
+macro_rules! snapshot {
+    ($name:ident, $format:expr $(, $arg:expr)*) => {
+        #[test]
+        fn $name() {
+            insta::assert_snapshot!(report($format, &[$($arg),*]));
+        }
+    };
+}
+
+snapshot!(markdown, "markdown");
+snapshot!(md_table, "md-table");
+snapshot!(narrative, "narrative");
+snapshot!(text, "text");
+snapshot!(terminal, "terminal");
+snapshot!(asciidoc, "asciidoc");
+snapshot!(json, "json");
+snapshot!(yaml, "yaml");
+snapshot!(csv, "csv");
+snapshot!(html, "html");
+snapshot!(html_fragment, "html-fragment");
+snapshot!(discord, "discord");
+snapshot!(atom, "atom");
+snapshot!(waybar, "waybar");
+snapshot!(confluence, "confluence");
+snapshot!(quickfix, "quickfix");
+snapshot!(mermaid, "mermaid");
+
+// One entry per section, with my comments and reviews quoted.
+const TRUNCATED: [&str; 4] = [
+    "--max-items",
+    "1",
+    "--show-comment-excerpts",
+    "--show-review-bodies",
+];
+
+macro_rules! truncated_snapshot {
+    ($name:ident, $format:expr) => {
+        #[test]
+        fn $name() {
+            insta::assert_snapshot!(report($format, &TRUNCATED));
+        }
+    };
+}
+
+truncated_snapshot!(markdown_truncated, "markdown");
+truncated_snapshot!(md_table_truncated, "md-table");
+truncated_snapshot!(narrative_truncated, "narrative");
+truncated_snapshot!(text_truncated, "text");
+
+#[test]
+fn email() {
+    // The Date header is the time of the run.
+    let r = report(
+        "email",
+        &[
+            "--email-from",
+            "alice@example.com",
+            "--email-to",
+            "team@example.com",
+        ],
+    );
+    let r: Vec<&str> = r.lines().filter(|l| !l.starts_with("Date: ")).collect();
+    insta::assert_snapshot!(r.join("\n"));
+}
@@ -0,0 +1,60 @@
+//! Snapshots of `render_to_string` for `fixtures/events.json` in every
+//! `Format`, through the library rather than the binary.
+//!
+//! Run `cargo insta review` (or set `INSTA_UPDATE=always`) after an
+//! intended change to the output.
+
+use chrono::prelude::*;
+use mygithubstatus::{parse_events, read_events_file, render_to_string, Format, RenderOptions};
+
+fn render(format: &str, opts: RenderOptions) -> String {
+    // Day boundaries and times are local; every test sets the same zone.
+    std::env::set_var("TZ", "UTC");
+    let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/events.json");
+    let events = read_events_file(fixture).expect("reading the fixture");
+    let start = Local.with_ymd_and_hms(2026, 10, 12, 0, 0, 0).unwrap();
+    let end = Local.with_ymd_and_hms(2026, 10, 15, 0, 0, 0).unwrap();
+    let events = parse_events(events.into_iter().map(Box::new), &start, &end);
+    let format: Format = format.parse().expect("a known format");
+    render_to_string(&events, format, &opts).expect("rendering")
+}
+
+macro_rules! snapshot {
+    ($name:ident, $format:expr) => {
+        #[test]
+        fn $name() {
+            insta::assert_snapshot!(render($format, RenderOptions::default()));
+        }
+    };
+}
+
+snapshot!(markdown, "markdown");
+snapshot!(md_table, "md-table");
+snapshot!(narrative, "narrative");
+snapshot!(text, "text");
+snapshot!(terminal, "terminal");
+snapshot!(asciidoc, "asciidoc");
+snapshot!(json, "json");
+snapshot!(yaml, "yaml");
+snapshot!(csv, "csv");
+snapshot!(html, "html");
+snapshot!(html_fragment, "html-fragment");
+snapshot!(discord, "discord");
+snapshot!(atom, "atom");
+snapshot!(email, "email");
+snapshot!(waybar, "waybar");
+snapshot!(confluence, "confluence");
+snapshot!(quickfix, "quickfix");
+snapshot!(mermaid, "mermaid");
+
+#[test]
+fn markdown_with_options() {
+    let opts = RenderOptions {
+        no_emoji: true,
+        comment_bodies: true,
+        review_bodies: true,
+        max_items: Some(1),
+        ..Default::default()
+    };
+    insta::assert_snapshot!(render("markdown", opts));
+}